@@ -0,0 +1,13 @@
+ALTER TABLE media ADD COLUMN IF NOT EXISTS disc_size VARCHAR(16);
+ALTER TABLE media ADD COLUMN IF NOT EXISTS speed VARCHAR(16);
+ALTER TABLE media ADD COLUMN IF NOT EXISTS release_type VARCHAR(16);
+ALTER TABLE media ADD COLUMN IF NOT EXISTS weight INT;
+ALTER TABLE media ADD COLUMN IF NOT EXISTS packaging VARCHAR(32)[] NOT NULL DEFAULT '{}';
+
+-- Media types used to be parsed case-insensitively. Store them with the exact spelling of the media type names.
+UPDATE media SET media_type = CASE lower(media_type)
+    WHEN 'vinyl' THEN 'Vinyl'
+    WHEN 'tape' THEN 'Tape'
+    WHEN 'cd' THEN 'Cd'
+    ELSE media_type
+  END;
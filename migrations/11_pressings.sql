@@ -0,0 +1,17 @@
+ALTER TABLE media ADD COLUMN IF NOT EXISTS pressing_plant VARCHAR(255);
+ALTER TABLE media ADD COLUMN IF NOT EXISTS country VARCHAR(255);
+ALTER TABLE media ADD COLUMN IF NOT EXISTS variant VARCHAR(255);
+ALTER TABLE media ADD COLUMN IF NOT EXISTS edition_number INT;
+ALTER TABLE media ADD COLUMN IF NOT EXISTS edition_size INT;
+
+CREATE TABLE IF NOT EXISTS matrices (
+  id SERIAL PRIMARY KEY,
+  media_id VARCHAR(21) NOT NULL,
+  side VARCHAR(8) NOT NULL,
+  runout TEXT NOT NULL,
+  -- Runout etchings are transcribed with varying spacing, dashes and case. Searches match against the letters and
+  -- digits only.
+  runout_key TEXT GENERATED ALWAYS AS (upper(regexp_replace(runout, '[^[:alnum:]]', '', 'g'))) STORED
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS matrices_media_side_idx ON matrices (media_id, side);
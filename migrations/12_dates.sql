@@ -0,0 +1,8 @@
+-- Release dates may only be known to the year or month. The date is stored as the first day of that period.
+ALTER TABLE media ALTER COLUMN release_date TYPE DATE USING release_date::DATE;
+ALTER TABLE media ADD COLUMN IF NOT EXISTS release_date_precision VARCHAR(8) NOT NULL DEFAULT 'Day';
+ALTER TABLE media ALTER COLUMN purchase_date TYPE DATE USING purchase_date::DATE;
+
+-- Timestamps were stored in UTC without an offset
+ALTER TABLE media ALTER COLUMN created_at TYPE TIMESTAMPTZ USING created_at AT TIME ZONE 'UTC';
+ALTER TABLE media ALTER COLUMN modified_at TYPE TIMESTAMPTZ USING modified_at AT TIME ZONE 'UTC';
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS condition_gradings (
+  id SERIAL PRIMARY KEY,
+  media_id VARCHAR(21) NOT NULL,
+  graded_at TIMESTAMPTZ NOT NULL,
+  grader VARCHAR(255),
+  media_condition VARCHAR(32) NOT NULL,
+  sleeve_condition VARCHAR(32) NOT NULL,
+  notes TEXT NOT NULL DEFAULT ''
+);
+
+CREATE INDEX IF NOT EXISTS condition_gradings_media_idx ON condition_gradings (media_id, graded_at);
+
+-- The current conditions become the first grading of every media entry
+INSERT INTO condition_gradings (media_id, graded_at, media_condition, sleeve_condition)
+SELECT id, modified_at, media_condition, sleeve_condition FROM media
+WHERE NOT EXISTS (SELECT 1 FROM condition_gradings WHERE condition_gradings.media_id = media.id);
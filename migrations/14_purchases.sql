@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS shops (
+  id VARCHAR(21) PRIMARY KEY,
+  name VARCHAR(255) NOT NULL,
+  city VARCHAR(255),
+  online BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+-- Prices are stored in the minor unit of their currency, e.g. cents or yen
+ALTER TABLE media ADD COLUMN IF NOT EXISTS price BIGINT;
+ALTER TABLE media ADD COLUMN IF NOT EXISTS currency CHAR(3);
+ALTER TABLE media ADD COLUMN IF NOT EXISTS shop_id VARCHAR(21);
+ALTER TABLE media ADD COLUMN IF NOT EXISTS seller VARCHAR(255);
+ALTER TABLE media ADD COLUMN IF NOT EXISTS order_reference VARCHAR(255);
+
+CREATE INDEX IF NOT EXISTS media_shop_idx ON media (shop_id);
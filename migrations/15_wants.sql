@@ -0,0 +1,30 @@
+CREATE TABLE IF NOT EXISTS wants (
+  id VARCHAR(21) PRIMARY KEY,
+  title VARCHAR(255) NOT NULL,
+  media_type VARCHAR(32) NOT NULL,
+  priority VARCHAR(8) NOT NULL DEFAULT 'Normal',
+  -- The maximum price is stored in the minor unit of its currency, like purchase prices
+  max_price BIGINT,
+  max_price_currency CHAR(3),
+  min_condition VARCHAR(32),
+  notes TEXT NOT NULL DEFAULT '',
+  created_at TIMESTAMPTZ NOT NULL,
+  modified_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS wants_artists_rel (
+  id SERIAL PRIMARY KEY,
+  want_id VARCHAR(21) NOT NULL,
+  artist_id VARCHAR(21) NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS wants_artists_rel_want_artist_idx ON wants_artists_rel (want_id, artist_id);
+
+CREATE TABLE IF NOT EXISTS wants_label_rel (
+  id SERIAL PRIMARY KEY,
+  want_id VARCHAR(21) NOT NULL,
+  label_id VARCHAR(21) NOT NULL,
+  catalogue VARCHAR(255) NOT NULL DEFAULT ''
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS wants_label_rel_want_label_idx ON wants_label_rel (want_id, label_id);
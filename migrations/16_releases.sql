@@ -0,0 +1,73 @@
+-- A release describes the published record, a media entry is an owned copy of a release. Every existing media entry
+-- becomes a copy of its own release, which reuses the ID of the media entry.
+CREATE TABLE IF NOT EXISTS releases (
+  id VARCHAR(21) PRIMARY KEY,
+  title VARCHAR(255) NOT NULL,
+  media_type VARCHAR(32) NOT NULL,
+  disc_size VARCHAR(16),
+  speed VARCHAR(16),
+  release_type VARCHAR(16),
+  weight INTEGER,
+  packaging VARCHAR(32)[] NOT NULL DEFAULT '{}',
+  release_date DATE NOT NULL,
+  release_date_precision VARCHAR(8) NOT NULL DEFAULT 'Day',
+  created_at TIMESTAMPTZ NOT NULL,
+  modified_at TIMESTAMPTZ NOT NULL
+);
+
+INSERT INTO releases (
+  id, title, media_type, disc_size, speed, release_type, weight, packaging, release_date, release_date_precision,
+  created_at, modified_at
+)
+SELECT
+  id, title, media_type, disc_size, speed, release_type, weight, packaging, release_date, release_date_precision,
+  created_at, modified_at
+FROM media;
+
+ALTER TABLE releases ADD COLUMN search TSVECTOR GENERATED ALWAYS AS (
+  setweight(to_tsvector('simple', title), 'A')
+) STORED;
+
+CREATE INDEX IF NOT EXISTS releases_search_idx ON releases USING GIN (search);
+
+ALTER TABLE media ADD COLUMN IF NOT EXISTS release_id VARCHAR(21);
+UPDATE media SET release_id = id;
+ALTER TABLE media ALTER COLUMN release_id SET NOT NULL;
+
+CREATE INDEX IF NOT EXISTS media_release_idx ON media (release_id);
+
+-- Artists, labels and catalogue numbers belong to the release
+ALTER TABLE media_artists_rel RENAME TO release_artists_rel;
+ALTER TABLE release_artists_rel RENAME COLUMN media_id TO release_id;
+ALTER INDEX media_artists_rel_pkey RENAME TO release_artists_rel_pkey;
+
+ALTER TABLE media_label_rel RENAME TO release_label_rel;
+ALTER TABLE release_label_rel RENAME COLUMN media_id TO release_id;
+ALTER INDEX media_label_rel_pkey RENAME TO release_label_rel_pkey;
+ALTER INDEX media_label_rel_media_label_idx RENAME TO release_label_rel_release_label_idx;
+ALTER INDEX media_label_rel_search_idx RENAME TO release_label_rel_search_idx;
+
+-- Tracks keep their belongs_to column, which now references the release. The IDs stay the same, as every release
+-- reuses the ID of the media entry it was created from.
+UPDATE links SET owner = 'Release' WHERE owner = 'Media';
+
+-- The search vector of media entries includes the title and has to be rebuilt without it
+DROP INDEX IF EXISTS media_search_idx;
+ALTER TABLE media DROP COLUMN IF EXISTS search;
+
+ALTER TABLE media
+  DROP COLUMN IF EXISTS title,
+  DROP COLUMN IF EXISTS media_type,
+  DROP COLUMN IF EXISTS disc_size,
+  DROP COLUMN IF EXISTS speed,
+  DROP COLUMN IF EXISTS release_type,
+  DROP COLUMN IF EXISTS weight,
+  DROP COLUMN IF EXISTS packaging,
+  DROP COLUMN IF EXISTS release_date,
+  DROP COLUMN IF EXISTS release_date_precision;
+
+ALTER TABLE media ADD COLUMN search TSVECTOR GENERATED ALWAYS AS (
+  setweight(to_tsvector('simple', notes), 'D')
+) STORED;
+
+CREATE INDEX IF NOT EXISTS media_search_idx ON media USING GIN (search);
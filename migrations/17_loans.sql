@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS loans (
+  id VARCHAR(21) PRIMARY KEY,
+  media_id VARCHAR(21) NOT NULL,
+  borrower VARCHAR(255) NOT NULL,
+  contact VARCHAR(255),
+  loan_date DATE NOT NULL,
+  due_date DATE,
+  return_date DATE,
+  notes TEXT NOT NULL DEFAULT '',
+  created_at TIMESTAMPTZ NOT NULL,
+  modified_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS loans_media_idx ON loans (media_id);
+
+-- A media entry can only be lent to one borrower at a time
+CREATE UNIQUE INDEX IF NOT EXISTS loans_open_media_idx ON loans (media_id) WHERE return_date IS NULL;
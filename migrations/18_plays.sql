@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS plays (
+  id VARCHAR(21) PRIMARY KEY,
+  media_id VARCHAR(21) NOT NULL,
+  played_at TIMESTAMPTZ NOT NULL,
+  -- Empty if the whole record was played
+  sides VARCHAR(8)[] NOT NULL DEFAULT '{}',
+  notes TEXT NOT NULL DEFAULT '',
+  created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS plays_media_idx ON plays (media_id, played_at DESC);
+CREATE INDEX IF NOT EXISTS plays_played_at_idx ON plays (played_at DESC);
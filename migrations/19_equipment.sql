@@ -0,0 +1,44 @@
+CREATE TABLE IF NOT EXISTS equipment (
+  id VARCHAR(21) PRIMARY KEY,
+  kind VARCHAR(16) NOT NULL,
+  name VARCHAR(255) NOT NULL,
+  manufacturer VARCHAR(255),
+  model VARCHAR(255),
+  -- The cartridge a stylus is mounted on
+  cartridge_id VARCHAR(21),
+  active BOOLEAN NOT NULL DEFAULT FALSE,
+  lifetime_hours INT,
+  notes TEXT NOT NULL DEFAULT '',
+  created_at TIMESTAMPTZ NOT NULL,
+  modified_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS equipment_cartridge_idx ON equipment (cartridge_id);
+
+-- Only one turntable and one cartridge are in use at a time, and each cartridge holds one stylus
+CREATE UNIQUE INDEX IF NOT EXISTS equipment_active_idx ON equipment (kind) WHERE active AND kind <> 'Stylus';
+CREATE UNIQUE INDEX IF NOT EXISTS equipment_active_stylus_idx ON equipment (cartridge_id) WHERE active AND kind = 'Stylus';
+
+-- Plays of records are attributed to the equipment in use when they are logged. The duration in seconds is the runtime
+-- of the played record sides.
+ALTER TABLE plays ADD COLUMN IF NOT EXISTS duration INT NOT NULL DEFAULT 0;
+ALTER TABLE plays ADD COLUMN IF NOT EXISTS turntable_id VARCHAR(21);
+ALTER TABLE plays ADD COLUMN IF NOT EXISTS cartridge_id VARCHAR(21);
+ALTER TABLE plays ADD COLUMN IF NOT EXISTS stylus_id VARCHAR(21);
+
+-- Plays of deleted media entries are kept without a media entry, so the wear they caused is not lost
+ALTER TABLE plays ALTER COLUMN media_id DROP NOT NULL;
+
+CREATE INDEX IF NOT EXISTS plays_turntable_idx ON plays (turntable_id);
+CREATE INDEX IF NOT EXISTS plays_cartridge_idx ON plays (cartridge_id);
+CREATE INDEX IF NOT EXISTS plays_stylus_idx ON plays (stylus_id);
+
+-- Only records wear the stylus, i.e. the sided tracks of vinyl and box sets
+UPDATE plays SET duration = COALESCE((
+  SELECT SUM(tracks.duration) FROM tracks
+  JOIN media ON (media.release_id = tracks.belongs_to)
+  JOIN releases ON (releases.id = media.release_id)
+  WHERE media.id = plays.media_id AND releases.media_type IN ('Vinyl', 'BoxSet')
+  AND tracks.record_side IS NOT NULL AND NOT tracks.digital
+  AND (cardinality(plays.sides) = 0 OR tracks.record_side = ANY(plays.sides))
+), 0);
@@ -0,0 +1,28 @@
+ALTER TABLE media ADD COLUMN IF NOT EXISTS search TSVECTOR GENERATED ALWAYS AS (
+  setweight(to_tsvector('simple', title), 'A') ||
+  setweight(to_tsvector('simple', catalogue), 'B') ||
+  setweight(to_tsvector('simple', notes), 'D')
+) STORED;
+
+ALTER TABLE artists ADD COLUMN IF NOT EXISTS search TSVECTOR GENERATED ALWAYS AS (
+  to_tsvector('simple', name)
+) STORED;
+
+ALTER TABLE labels ADD COLUMN IF NOT EXISTS search TSVECTOR GENERATED ALWAYS AS (
+  to_tsvector('simple', name)
+) STORED;
+
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS search TSVECTOR GENERATED ALWAYS AS (
+  to_tsvector('simple', title)
+) STORED;
+
+CREATE INDEX IF NOT EXISTS media_search_idx ON media USING GIN (search);
+CREATE INDEX IF NOT EXISTS artists_search_idx ON artists USING GIN (search);
+CREATE INDEX IF NOT EXISTS labels_search_idx ON labels USING GIN (search);
+CREATE INDEX IF NOT EXISTS tracks_search_idx ON tracks USING GIN (search);
+
+-- Search highlights wrap the matched terms in HTML tags, so the text around them has to be escaped first. Escaping also
+-- keeps `ts_headline` from parsing markup in the text as tags and dropping it.
+CREATE OR REPLACE FUNCTION html_escape(text) RETURNS text AS $$
+  SELECT replace(replace(replace(replace(replace($1, '&', '&amp;'), '<', '&lt;'), '>', '&gt;'), '"', '&quot;'), '''', '&#39;')
+$$ LANGUAGE SQL IMMUTABLE STRICT;
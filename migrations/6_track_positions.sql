@@ -0,0 +1,10 @@
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS position INT NOT NULL DEFAULT 0;
+
+-- Number existing tracks per media entry and record side. Their original order is unknown, so they are numbered in
+-- the order of their IDs.
+UPDATE tracks SET position = numbered.position
+FROM (
+  SELECT id, ROW_NUMBER() OVER (PARTITION BY belongs_to, record_side ORDER BY id) AS position
+  FROM tracks
+) AS numbered
+WHERE tracks.id = numbered.id;
@@ -0,0 +1,31 @@
+-- Catalogue numbers are assigned per label, so co-released records carry one for every label. Move the catalogue
+-- number from the media entry onto its label relations.
+DELETE FROM media_label_rel AS a
+USING media_label_rel AS b
+WHERE a.media_id = b.media_id AND a.label_id = b.label_id AND a.id > b.id;
+
+CREATE UNIQUE INDEX IF NOT EXISTS media_label_rel_media_label_idx ON media_label_rel (media_id, label_id);
+
+ALTER TABLE media_label_rel ADD COLUMN IF NOT EXISTS catalogue VARCHAR(255) NOT NULL DEFAULT '';
+
+UPDATE media_label_rel SET catalogue = media.catalogue
+FROM media
+WHERE media.id = media_label_rel.media_id;
+
+ALTER TABLE media_label_rel ADD COLUMN IF NOT EXISTS search TSVECTOR GENERATED ALWAYS AS (
+  to_tsvector('simple', catalogue)
+) STORED;
+
+CREATE INDEX IF NOT EXISTS media_label_rel_search_idx ON media_label_rel USING GIN (search);
+
+-- The search vector of media entries includes the catalogue number and has to be rebuilt without it
+DROP INDEX IF EXISTS media_search_idx;
+ALTER TABLE media DROP COLUMN IF EXISTS search;
+ALTER TABLE media DROP COLUMN IF EXISTS catalogue;
+
+ALTER TABLE media ADD COLUMN search TSVECTOR GENERATED ALWAYS AS (
+  setweight(to_tsvector('simple', title), 'A') ||
+  setweight(to_tsvector('simple', notes), 'D')
+) STORED;
+
+CREATE INDEX IF NOT EXISTS media_search_idx ON media USING GIN (search);
@@ -0,0 +1,40 @@
+CREATE TABLE IF NOT EXISTS links (
+  id SERIAL PRIMARY KEY,
+  owner VARCHAR(16) NOT NULL,
+  owner_id VARCHAR(22) NOT NULL,
+  kind VARCHAR(32) NOT NULL,
+  url TEXT NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS links_owner_url_idx ON links (owner, owner_id, url);
+
+-- Move the free-text URLs of artists, labels and tracks into the links table. The text is split on whitespace, commas
+-- and semicolons, everything which does not look like a HTTP(S) URL is dropped.
+INSERT INTO links (owner, owner_id, kind, url)
+SELECT
+  split.owner,
+  split.owner_id,
+  CASE
+    WHEN host.name = 'discogs.com' THEN 'Discogs'
+    WHEN host.name = 'musicbrainz.org' THEN 'MusicBrainz'
+    WHEN host.name = 'bandcamp.com' OR host.name LIKE '%.bandcamp.com' THEN 'Bandcamp'
+    WHEN host.name IN ('spotify.com', 'open.spotify.com') THEN 'Spotify'
+    ELSE 'Other'
+  END,
+  split.url
+FROM (
+  SELECT 'Artist' AS owner, id AS owner_id, regexp_split_to_table(urls, '[\s,;]+') AS url FROM artists
+  UNION ALL
+  SELECT 'Label', id, regexp_split_to_table(urls, '[\s,;]+') FROM labels
+  UNION ALL
+  SELECT 'Track', id, regexp_split_to_table(urls, '[\s,;]+') FROM tracks
+) AS split
+CROSS JOIN LATERAL (
+  SELECT lower(substring(split.url FROM '^https?://(?:www\.)?([^/:?#]+)')) AS name
+) AS host
+WHERE split.url ~* '^https?://[^/:?#]+'
+ON CONFLICT DO NOTHING;
+
+ALTER TABLE artists DROP COLUMN IF EXISTS urls;
+ALTER TABLE labels DROP COLUMN IF EXISTS urls;
+ALTER TABLE tracks DROP COLUMN IF EXISTS urls;
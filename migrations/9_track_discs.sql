@@ -0,0 +1,23 @@
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS disc INT NOT NULL DEFAULT 1;
+ALTER TABLE tracks ALTER COLUMN record_side DROP NOT NULL;
+
+UPDATE tracks SET record_side = upper(trim(record_side));
+
+-- CDs have no sides. Number their tracks across the former sides, then drop the sides.
+UPDATE tracks SET position = numbered.position
+FROM (
+  SELECT tracks.id, ROW_NUMBER() OVER (PARTITION BY tracks.belongs_to ORDER BY tracks.record_side, tracks.position, tracks.id) AS position
+  FROM tracks
+  JOIN media ON (media.id = tracks.belongs_to)
+  WHERE media.media_type = 'Cd'
+) AS numbered
+WHERE tracks.id = numbered.id;
+
+UPDATE tracks SET record_side = NULL
+FROM media
+WHERE media.id = tracks.belongs_to AND media.media_type = 'Cd';
+
+-- Vinyl sides are lettered across discs, two sides per disc: A and B are on disc 1, C and D on disc 2 and so on
+UPDATE tracks SET disc = (ascii(tracks.record_side) - ascii('A')) / 2 + 1
+FROM media
+WHERE media.id = tracks.belongs_to AND media.media_type = 'Vinyl' AND tracks.record_side ~ '^[A-Z]$';
@@ -6,9 +6,9 @@ pub struct ConfigError {
 
 impl ConfigError {
     pub fn new<M: Into<String>>(message: M) -> Self {
-        return Self {
+        Self {
             message: message.into(),
-        };
+        }
     }
 }
 
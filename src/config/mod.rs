@@ -1,6 +1,5 @@
 use dotenvy::dotenv;
 use serde::Deserialize;
-use toml;
 
 use std::{env, fs};
 
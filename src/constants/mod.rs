@@ -1 +1,13 @@
 pub const NANOID_LEN: usize = 21;
+
+/// Number of media entries returned per page if no limit is requested.
+pub const DEFAULT_PAGE_LIMIT: i64 = 50;
+
+/// Maximum number of media entries returned per page.
+pub const MAX_PAGE_LIMIT: i64 = 500;
+
+/// Number of labels and artists listed in the collection statistics if no number is requested.
+pub const DEFAULT_STATS_TOP: i64 = 10;
+
+/// Share of its lifetime in percent after which a stylus is reported as worn.
+pub const STYLUS_WEAR_WARNING_PERCENT: i64 = 90;
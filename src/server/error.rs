@@ -6,9 +6,9 @@ pub struct ServerError {
 
 impl ServerError {
     pub fn new<M: Into<String>>(message: M) -> Self {
-        return Self {
+        Self {
             message: message.into(),
-        };
+        }
     }
 }
 
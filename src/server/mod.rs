@@ -46,11 +46,113 @@ impl Server {
             .route("/", routing::post(routes::create_media_entry))
             .route("/", routing::get(routes::get_media_entries))
             .route("/:id", routing::get(routes::get_media_entry))
-            .route("/:id", routing::post(routes::update_media_entry))
-            .route("/:id", routing::delete(routes::delete_media_entry));
+            .route("/:id", routing::patch(routes::update_media_entry))
+            .route("/:id", routing::delete(routes::delete_media_entry))
+            .route(
+                "/:id/gradings",
+                routing::get(routes::gradings::get_gradings),
+            )
+            .route(
+                "/:id/gradings",
+                routing::post(routes::gradings::add_grading),
+            )
+            .route("/:id/loans", routing::get(routes::loans::get_media_loans))
+            .route("/:id/loans", routing::post(routes::loans::create_loan))
+            .route("/:id/plays", routing::get(routes::plays::get_media_plays))
+            .route("/:id/plays", routing::post(routes::plays::create_play))
+            .route("/:id/tracks", routing::get(routes::tracks::get_tracks))
+            .route("/:id/tracks", routing::put(routes::tracks::replace_tracks))
+            .route("/:id/tracks", routing::post(routes::tracks::add_track))
+            .route(
+                "/:id/tracks/:track_id",
+                routing::patch(routes::tracks::update_track),
+            )
+            .route(
+                "/:id/tracks/:track_id",
+                routing::delete(routes::tracks::delete_track),
+            );
+
+        let artists_router = Router::new()
+            .route("/", routing::get(routes::artists::get_artists))
+            .route("/", routing::post(routes::artists::create_artist))
+            .route("/:id", routing::get(routes::artists::get_artist))
+            .route("/:id", routing::patch(routes::artists::update_artist))
+            .route("/:id", routing::delete(routes::artists::delete_artist))
+            .route(
+                "/:id/media",
+                routing::get(routes::artists::get_artist_media_entries),
+            );
+
+        let equipment_router = Router::new()
+            .route("/", routing::get(routes::equipment::get_equipment_list))
+            .route("/", routing::post(routes::equipment::create_equipment))
+            .route("/:id", routing::get(routes::equipment::get_equipment))
+            .route("/:id", routing::patch(routes::equipment::update_equipment))
+            .route("/:id", routing::delete(routes::equipment::delete_equipment));
+
+        let labels_router = Router::new()
+            .route("/", routing::get(routes::labels::get_labels))
+            .route("/", routing::post(routes::labels::create_label))
+            .route("/:id", routing::get(routes::labels::get_label))
+            .route("/:id", routing::patch(routes::labels::update_label))
+            .route("/:id", routing::delete(routes::labels::delete_label))
+            .route(
+                "/:id/media",
+                routing::get(routes::labels::get_label_media_entries),
+            );
+
+        let shops_router = Router::new()
+            .route("/", routing::get(routes::shops::get_shops))
+            .route("/", routing::post(routes::shops::create_shop))
+            .route("/:id", routing::get(routes::shops::get_shop))
+            .route("/:id", routing::patch(routes::shops::update_shop))
+            .route("/:id", routing::delete(routes::shops::delete_shop))
+            .route(
+                "/:id/media",
+                routing::get(routes::shops::get_shop_media_entries),
+            );
+
+        let plays_router = Router::new()
+            .route("/", routing::get(routes::plays::get_plays))
+            .route(
+                "/least-recent",
+                routing::get(routes::plays::get_least_recently_played),
+            )
+            .route("/:id", routing::delete(routes::plays::delete_play));
+
+        let releases_router = Router::new()
+            .route("/", routing::get(routes::releases::get_releases))
+            .route("/:id", routing::get(routes::releases::get_release))
+            .route("/:id/copies", routing::post(routes::releases::create_copy));
+
+        let loans_router = Router::new()
+            .route("/", routing::get(routes::loans::get_loans))
+            .route("/overdue", routing::get(routes::loans::get_overdue_loans))
+            .route("/:id", routing::get(routes::loans::get_loan))
+            .route("/:id", routing::patch(routes::loans::update_loan))
+            .route("/:id", routing::delete(routes::loans::delete_loan));
+
+        let wants_router = Router::new()
+            .route("/", routing::get(routes::wants::get_wants))
+            .route("/", routing::post(routes::wants::create_want))
+            .route("/:id", routing::get(routes::wants::get_want))
+            .route("/:id", routing::patch(routes::wants::update_want))
+            .route("/:id", routing::delete(routes::wants::delete_want))
+            .route("/:id/convert", routing::post(routes::wants::convert_want));
 
         let router = Router::new()
             .nest("/api/media", vinyl_router)
+            .nest("/api/artists", artists_router)
+            .nest("/api/equipment", equipment_router)
+            .nest("/api/labels", labels_router)
+            .nest("/api/loans", loans_router)
+            .nest("/api/plays", plays_router)
+            .nest("/api/releases", releases_router)
+            .nest("/api/shops", shops_router)
+            .nest("/api/wants", wants_router)
+            .route("/api/search", routing::get(routes::search_media))
+            .route("/api/search/runout", routing::get(routes::search_runouts))
+            .route("/api/stats", routing::get(routes::get_stats))
             .layer(extract::Extension(store));
 
         let address: net::SocketAddr = match self.config.server.address.parse() {
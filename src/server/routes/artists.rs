@@ -0,0 +1,206 @@
+use axum::{
+    extract::{Extension, Json, Path, Query},
+    http::StatusCode,
+    response::IntoResponse,
+};
+
+use crate::{
+    constants,
+    server::routes::list_media_entries,
+    store::{error::StoreErrorKind, Store},
+    types::{
+        ArtistResponse, CreateArtistRequest, DeleteArtistResponse, GetArtistsQuery,
+        GetArtistsResponse, GetMediaEntriesQuery, GetMediaEntriesResponse, MediaFilter, MediaSort,
+        UpdateArtistRequest,
+    },
+};
+
+pub async fn get_artists(
+    Query(query): Query<GetArtistsQuery>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    let limit = query.limit.unwrap_or(constants::DEFAULT_PAGE_LIMIT);
+    if !(1..=constants::MAX_PAGE_LIMIT).contains(&limit) {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetArtistsResponse::error(format!(
+                "Limit must be between 1 and {}",
+                constants::MAX_PAGE_LIMIT
+            )),
+        );
+    }
+
+    let offset = query.offset.unwrap_or(0);
+    if offset < 0 {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetArtistsResponse::error("Offset must not be negative"),
+        );
+    }
+
+    match store.get_artists(limit, offset).await {
+        Ok((artists, total)) => (StatusCode::OK, GetArtistsResponse::success(artists, total)),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            GetArtistsResponse::error(err.to_string()),
+        ),
+    }
+}
+
+pub async fn get_artist(
+    Path(artist_id): Path<String>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if artist_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            ArtistResponse::error("Invalid artist ID"),
+        );
+    }
+
+    match store.get_artist(artist_id).await {
+        Ok(artist) => (StatusCode::OK, ArtistResponse::success(artist)),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                ArtistResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ArtistResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+pub async fn create_artist(
+    Extension(store): Extension<Store>,
+    Json(payload): Json<CreateArtistRequest>,
+) -> impl IntoResponse {
+    if payload.name.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            ArtistResponse::error("Missing artist name"),
+        );
+    }
+
+    match store.create_artist(payload).await {
+        Ok(artist) => (StatusCode::CREATED, ArtistResponse::success(artist)),
+        Err(err) => match err.kind() {
+            StoreErrorKind::Invalid => (
+                StatusCode::BAD_REQUEST,
+                ArtistResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ArtistResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+pub async fn update_artist(
+    Path(artist_id): Path<String>,
+    Extension(store): Extension<Store>,
+    Json(payload): Json<UpdateArtistRequest>,
+) -> impl IntoResponse {
+    if artist_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            ArtistResponse::error("Invalid artist ID"),
+        );
+    }
+
+    if let Some(name) = &payload.name {
+        if name.is_empty() {
+            return (
+                StatusCode::BAD_REQUEST,
+                ArtistResponse::error("Artist name must not be empty"),
+            );
+        }
+    }
+
+    match store.update_artist(artist_id, payload).await {
+        Ok(artist) => (StatusCode::OK, ArtistResponse::success(artist)),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                ArtistResponse::error(err.to_string()),
+            ),
+            StoreErrorKind::Invalid => (
+                StatusCode::BAD_REQUEST,
+                ArtistResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ArtistResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+pub async fn delete_artist(
+    Path(artist_id): Path<String>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if artist_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            DeleteArtistResponse::error("Invalid artist ID"),
+        );
+    }
+
+    match store.delete_artist(artist_id).await {
+        Ok(_) => (StatusCode::OK, DeleteArtistResponse::success()),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                DeleteArtistResponse::error(err.to_string()),
+            ),
+            StoreErrorKind::Conflict => (
+                StatusCode::CONFLICT,
+                DeleteArtistResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                DeleteArtistResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+/// Lists every media entry the artist appears on. Supports the same pagination, sort and filter parameters as the
+/// media listing.
+pub async fn get_artist_media_entries(
+    Path(artist_id): Path<String>,
+    Query(query): Query<GetMediaEntriesQuery>,
+    Query(filter): Query<MediaFilter>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if artist_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetMediaEntriesResponse::error("Invalid artist ID"),
+        );
+    }
+
+    if let Err(err) = store.get_artist(artist_id.clone()).await {
+        return match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                GetMediaEntriesResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                GetMediaEntriesResponse::error(err.to_string()),
+            ),
+        };
+    }
+
+    let filter = MediaFilter {
+        artist: Some(artist_id),
+        ..filter
+    };
+
+    list_media_entries(&store, query, filter, MediaSort::default()).await
+}
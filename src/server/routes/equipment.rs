@@ -0,0 +1,183 @@
+use axum::{
+    extract::{Extension, Json, Path, Query},
+    http::StatusCode,
+    response::IntoResponse,
+};
+
+use crate::{
+    constants,
+    store::{error::StoreErrorKind, Store},
+    types::{
+        CreateEquipmentRequest, DeleteEquipmentResponse, EquipmentResponse,
+        GetEquipmentListResponse, GetEquipmentQuery, UpdateEquipmentRequest,
+    },
+};
+
+pub async fn get_equipment_list(
+    Query(query): Query<GetEquipmentQuery>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    let limit = query.limit.unwrap_or(constants::DEFAULT_PAGE_LIMIT);
+    if !(1..=constants::MAX_PAGE_LIMIT).contains(&limit) {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetEquipmentListResponse::error(format!(
+                "Limit must be between 1 and {}",
+                constants::MAX_PAGE_LIMIT
+            )),
+        );
+    }
+
+    let offset = query.offset.unwrap_or(0);
+    if offset < 0 {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetEquipmentListResponse::error("Offset must not be negative"),
+        );
+    }
+
+    match store
+        .get_equipment_list(limit, offset, query.kind, query.active)
+        .await
+    {
+        Ok((equipment, total)) => (
+            StatusCode::OK,
+            GetEquipmentListResponse::success(equipment, total),
+        ),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            GetEquipmentListResponse::error(err.to_string()),
+        ),
+    }
+}
+
+pub async fn get_equipment(
+    Path(equipment_id): Path<String>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if equipment_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            EquipmentResponse::error("Invalid equipment ID"),
+        );
+    }
+
+    match store.get_equipment(equipment_id).await {
+        Ok(equipment) => (StatusCode::OK, EquipmentResponse::success(equipment)),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                EquipmentResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                EquipmentResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+pub async fn create_equipment(
+    Extension(store): Extension<Store>,
+    Json(payload): Json<CreateEquipmentRequest>,
+) -> impl IntoResponse {
+    if payload.name.trim().is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            EquipmentResponse::error("Missing equipment name"),
+        );
+    }
+
+    if let Some(cartridge_id) = &payload.cartridge_id {
+        if cartridge_id.len() != constants::NANOID_LEN {
+            return (
+                StatusCode::BAD_REQUEST,
+                EquipmentResponse::error("Invalid cartridge ID"),
+            );
+        }
+    }
+
+    match store.create_equipment(payload).await {
+        Ok(equipment) => (StatusCode::CREATED, EquipmentResponse::success(equipment)),
+        Err(err) => match err.kind() {
+            StoreErrorKind::Invalid => (
+                StatusCode::BAD_REQUEST,
+                EquipmentResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                EquipmentResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+pub async fn update_equipment(
+    Path(equipment_id): Path<String>,
+    Extension(store): Extension<Store>,
+    Json(payload): Json<UpdateEquipmentRequest>,
+) -> impl IntoResponse {
+    if equipment_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            EquipmentResponse::error("Invalid equipment ID"),
+        );
+    }
+
+    if let Some(Some(cartridge_id)) = &payload.cartridge_id {
+        if cartridge_id.len() != constants::NANOID_LEN {
+            return (
+                StatusCode::BAD_REQUEST,
+                EquipmentResponse::error("Invalid cartridge ID"),
+            );
+        }
+    }
+
+    match store.update_equipment(equipment_id, payload).await {
+        Ok(equipment) => (StatusCode::OK, EquipmentResponse::success(equipment)),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                EquipmentResponse::error(err.to_string()),
+            ),
+            StoreErrorKind::Invalid => (
+                StatusCode::BAD_REQUEST,
+                EquipmentResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                EquipmentResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+pub async fn delete_equipment(
+    Path(equipment_id): Path<String>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if equipment_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            DeleteEquipmentResponse::error("Invalid equipment ID"),
+        );
+    }
+
+    match store.delete_equipment(equipment_id).await {
+        Ok(_) => (StatusCode::OK, DeleteEquipmentResponse::success()),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                DeleteEquipmentResponse::error(err.to_string()),
+            ),
+            StoreErrorKind::Conflict => (
+                StatusCode::CONFLICT,
+                DeleteEquipmentResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                DeleteEquipmentResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
@@ -0,0 +1,75 @@
+use axum::{
+    extract::{Extension, Json, Path, Query},
+    http::StatusCode,
+    response::IntoResponse,
+};
+
+use crate::{
+    constants,
+    store::{error::StoreErrorKind, Store},
+    types::{CreateGradingRequest, GetGradingsResponse, GradingResponse, GradingScaleQuery},
+};
+
+/// Returns the condition grading history of a media entry, oldest grading first.
+pub async fn get_gradings(
+    Path(media_id): Path<String>,
+    Query(scale): Query<GradingScaleQuery>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if media_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetGradingsResponse::error("Invalid media ID"),
+        );
+    }
+
+    match store.get_gradings(media_id).await {
+        Ok(mut gradings) => {
+            gradings
+                .iter_mut()
+                .for_each(|grading| grading.set_scale(scale.scale));
+            (StatusCode::OK, GetGradingsResponse::success(gradings))
+        }
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                GetGradingsResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                GetGradingsResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+pub async fn add_grading(
+    Path(media_id): Path<String>,
+    Query(scale): Query<GradingScaleQuery>,
+    Extension(store): Extension<Store>,
+    Json(payload): Json<CreateGradingRequest>,
+) -> impl IntoResponse {
+    if media_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            GradingResponse::error("Invalid media ID"),
+        );
+    }
+
+    match store.add_grading(media_id, payload).await {
+        Ok(mut grading) => {
+            grading.set_scale(scale.scale);
+            (StatusCode::CREATED, GradingResponse::success(grading))
+        }
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                GradingResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                GradingResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
@@ -0,0 +1,205 @@
+use axum::{
+    extract::{Extension, Json, Path, Query},
+    http::StatusCode,
+    response::IntoResponse,
+};
+
+use crate::{
+    constants,
+    server::routes::list_media_entries,
+    store::{error::StoreErrorKind, Store},
+    types::{
+        CreateLabelRequest, DeleteLabelResponse, GetLabelsQuery, GetLabelsResponse,
+        GetMediaEntriesQuery, GetMediaEntriesResponse, LabelResponse, MediaFilter, MediaSort,
+        UpdateLabelRequest,
+    },
+};
+
+pub async fn get_labels(
+    Query(query): Query<GetLabelsQuery>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    let limit = query.limit.unwrap_or(constants::DEFAULT_PAGE_LIMIT);
+    if !(1..=constants::MAX_PAGE_LIMIT).contains(&limit) {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetLabelsResponse::error(format!(
+                "Limit must be between 1 and {}",
+                constants::MAX_PAGE_LIMIT
+            )),
+        );
+    }
+
+    let offset = query.offset.unwrap_or(0);
+    if offset < 0 {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetLabelsResponse::error("Offset must not be negative"),
+        );
+    }
+
+    match store.get_labels(limit, offset).await {
+        Ok((labels, total)) => (StatusCode::OK, GetLabelsResponse::success(labels, total)),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            GetLabelsResponse::error(err.to_string()),
+        ),
+    }
+}
+
+pub async fn get_label(
+    Path(label_id): Path<String>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if label_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            LabelResponse::error("Invalid label ID"),
+        );
+    }
+
+    match store.get_label(label_id).await {
+        Ok(label) => (StatusCode::OK, LabelResponse::success(label)),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => {
+                (StatusCode::NOT_FOUND, LabelResponse::error(err.to_string()))
+            }
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                LabelResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+pub async fn create_label(
+    Extension(store): Extension<Store>,
+    Json(payload): Json<CreateLabelRequest>,
+) -> impl IntoResponse {
+    if payload.name.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            LabelResponse::error("Missing label name"),
+        );
+    }
+
+    match store.create_label(payload).await {
+        Ok(label) => (StatusCode::CREATED, LabelResponse::success(label)),
+        Err(err) => match err.kind() {
+            StoreErrorKind::Invalid => (
+                StatusCode::BAD_REQUEST,
+                LabelResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                LabelResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+pub async fn update_label(
+    Path(label_id): Path<String>,
+    Extension(store): Extension<Store>,
+    Json(payload): Json<UpdateLabelRequest>,
+) -> impl IntoResponse {
+    if label_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            LabelResponse::error("Invalid label ID"),
+        );
+    }
+
+    if let Some(name) = &payload.name {
+        if name.is_empty() {
+            return (
+                StatusCode::BAD_REQUEST,
+                LabelResponse::error("Label name must not be empty"),
+            );
+        }
+    }
+
+    match store.update_label(label_id, payload).await {
+        Ok(label) => (StatusCode::OK, LabelResponse::success(label)),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => {
+                (StatusCode::NOT_FOUND, LabelResponse::error(err.to_string()))
+            }
+            StoreErrorKind::Invalid => (
+                StatusCode::BAD_REQUEST,
+                LabelResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                LabelResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+pub async fn delete_label(
+    Path(label_id): Path<String>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if label_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            DeleteLabelResponse::error("Invalid label ID"),
+        );
+    }
+
+    match store.delete_label(label_id).await {
+        Ok(_) => (StatusCode::OK, DeleteLabelResponse::success()),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                DeleteLabelResponse::error(err.to_string()),
+            ),
+            StoreErrorKind::Conflict => (
+                StatusCode::CONFLICT,
+                DeleteLabelResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                DeleteLabelResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+/// Lists the label's discography, i.e. every media entry released on the label. Entries are sorted by catalogue
+/// number unless another sort key is requested. Supports the same pagination, sort and filter parameters as the media
+/// listing.
+pub async fn get_label_media_entries(
+    Path(label_id): Path<String>,
+    Query(query): Query<GetMediaEntriesQuery>,
+    Query(filter): Query<MediaFilter>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if label_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetMediaEntriesResponse::error("Invalid label ID"),
+        );
+    }
+
+    if let Err(err) = store.get_label(label_id.clone()).await {
+        return match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                GetMediaEntriesResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                GetMediaEntriesResponse::error(err.to_string()),
+            ),
+        };
+    }
+
+    let filter = MediaFilter {
+        label: Some(label_id),
+        ..filter
+    };
+
+    list_media_entries(&store, query, filter, MediaSort::Catalogue).await
+}
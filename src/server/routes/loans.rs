@@ -0,0 +1,215 @@
+use axum::{
+    extract::{Extension, Json, Path, Query},
+    http::StatusCode,
+    response::IntoResponse,
+};
+
+use crate::{
+    constants,
+    store::{error::StoreErrorKind, Store},
+    types::{
+        CreateLoanRequest, DeleteLoanResponse, GetLoansQuery, GetLoansResponse, LoanResponse,
+        UpdateLoanRequest,
+    },
+};
+
+pub async fn get_loans(
+    Query(query): Query<GetLoansQuery>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    let limit = query.limit.unwrap_or(constants::DEFAULT_PAGE_LIMIT);
+    if !(1..=constants::MAX_PAGE_LIMIT).contains(&limit) {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetLoansResponse::error(format!(
+                "Limit must be between 1 and {}",
+                constants::MAX_PAGE_LIMIT
+            )),
+        );
+    }
+
+    let offset = query.offset.unwrap_or(0);
+    if offset < 0 {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetLoansResponse::error("Offset must not be negative"),
+        );
+    }
+
+    match store.get_loans(limit, offset, query.open).await {
+        Ok((loans, total)) => (StatusCode::OK, GetLoansResponse::success(loans, total)),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            GetLoansResponse::error(err.to_string()),
+        ),
+    }
+}
+
+/// Lists all open loans which are past their due date, longest overdue first.
+pub async fn get_overdue_loans(Extension(store): Extension<Store>) -> impl IntoResponse {
+    match store.get_overdue_loans().await {
+        Ok(loans) => {
+            let total = loans.len() as i64;
+            (StatusCode::OK, GetLoansResponse::success(loans, total))
+        }
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            GetLoansResponse::error(err.to_string()),
+        ),
+    }
+}
+
+pub async fn get_loan(
+    Path(loan_id): Path<String>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if loan_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            LoanResponse::error("Invalid loan ID"),
+        );
+    }
+
+    match store.get_loan(loan_id).await {
+        Ok(loan) => (StatusCode::OK, LoanResponse::success(loan)),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => {
+                (StatusCode::NOT_FOUND, LoanResponse::error(err.to_string()))
+            }
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                LoanResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+pub async fn update_loan(
+    Path(loan_id): Path<String>,
+    Extension(store): Extension<Store>,
+    Json(payload): Json<UpdateLoanRequest>,
+) -> impl IntoResponse {
+    if loan_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            LoanResponse::error("Invalid loan ID"),
+        );
+    }
+
+    match store.update_loan(loan_id, payload).await {
+        Ok(loan) => (StatusCode::OK, LoanResponse::success(loan)),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => {
+                (StatusCode::NOT_FOUND, LoanResponse::error(err.to_string()))
+            }
+            StoreErrorKind::Invalid => (
+                StatusCode::BAD_REQUEST,
+                LoanResponse::error(err.to_string()),
+            ),
+            StoreErrorKind::Conflict => {
+                (StatusCode::CONFLICT, LoanResponse::error(err.to_string()))
+            }
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                LoanResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+pub async fn delete_loan(
+    Path(loan_id): Path<String>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if loan_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            DeleteLoanResponse::error("Invalid loan ID"),
+        );
+    }
+
+    match store.delete_loan(loan_id).await {
+        Ok(_) => (StatusCode::OK, DeleteLoanResponse::success()),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                DeleteLoanResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                DeleteLoanResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+/// Lists the loan history of a media entry, most recent loan first.
+pub async fn get_media_loans(
+    Path(media_id): Path<String>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if media_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetLoansResponse::error("Invalid media ID"),
+        );
+    }
+
+    match store.get_media_loans(media_id).await {
+        Ok(loans) => {
+            let total = loans.len() as i64;
+            (StatusCode::OK, GetLoansResponse::success(loans, total))
+        }
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                GetLoansResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                GetLoansResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+/// Lends a media entry to someone. Fails with a conflict if the entry is lent out already.
+pub async fn create_loan(
+    Path(media_id): Path<String>,
+    Extension(store): Extension<Store>,
+    Json(payload): Json<CreateLoanRequest>,
+) -> impl IntoResponse {
+    if media_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            LoanResponse::error("Invalid media ID"),
+        );
+    }
+
+    if payload.borrower.trim().is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            LoanResponse::error("Missing borrower"),
+        );
+    }
+
+    match store.create_loan(media_id, payload).await {
+        Ok(loan) => (StatusCode::CREATED, LoanResponse::success(loan)),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => {
+                (StatusCode::NOT_FOUND, LoanResponse::error(err.to_string()))
+            }
+            StoreErrorKind::Invalid => (
+                StatusCode::BAD_REQUEST,
+                LoanResponse::error(err.to_string()),
+            ),
+            StoreErrorKind::Conflict => {
+                (StatusCode::CONFLICT, LoanResponse::error(err.to_string()))
+            }
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                LoanResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
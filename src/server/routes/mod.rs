@@ -1,58 +1,135 @@
 use axum::{
-    extract::{Extension, Json, Path},
+    extract::{Extension, Json, Path, Query},
     http::StatusCode,
     response::IntoResponse,
 };
-use nanoid::nanoid;
 
 use crate::{
     constants,
-    store::Store,
-    types::{GetMediaEntriesResponse, GetMediaEntryResponse},
+    store::{error::StoreErrorKind, Store},
+    types::{
+        CreateMediaEntryResponse, CreateMediaRequest, DeleteMediaEntryResponse, DeleteMediaQuery,
+        GetMediaEntriesQuery, GetMediaEntriesResponse, GetMediaEntryResponse, GradingScaleQuery,
+        MediaCursor, MediaFilter, MediaSort, RunoutSearchQuery, RunoutSearchResponse, SearchQuery,
+        SearchResponse, StatsQuery, StatsResponse, UpdateMediaEntryResponse, UpdateMediaRequest,
+    },
 };
 
+pub mod artists;
+pub mod equipment;
+pub mod gradings;
+pub mod labels;
+pub mod loans;
+pub mod plays;
+pub mod releases;
+pub mod shops;
+pub mod tracks;
+pub mod wants;
+
 pub async fn create_media_entry(
+    Query(scale): Query<GradingScaleQuery>,
+    Extension(store): Extension<Store>,
+    Json(payload): Json<CreateMediaRequest>,
+) -> impl IntoResponse {
+    if payload.title.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            CreateMediaEntryResponse::error("Missing media title"),
+        );
+    }
+
+    match store.create_media(payload).await {
+        Ok(mut media_entry) => {
+            media_entry.set_scale(scale.scale);
+            (
+                StatusCode::CREATED,
+                CreateMediaEntryResponse::success(media_entry),
+            )
+        }
+        Err(err) => match err.kind() {
+            StoreErrorKind::Invalid => (
+                StatusCode::BAD_REQUEST,
+                CreateMediaEntryResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                CreateMediaEntryResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+pub async fn get_media_entries(
+    Query(query): Query<GetMediaEntriesQuery>,
+    Query(filter): Query<MediaFilter>,
     Extension(store): Extension<Store>,
-    // Json(payload): Json<CreateVinylRequest>,
 ) -> impl IntoResponse {
-    // let vinyl = Media {
-    //     id: nanoid!(),
-    //     title: payload.title,
-    //     artists: payload.artists,
-    //     release_year: payload.release_year,
-    //     tracks: payload.tracks,
-    //     bought_in_condition: payload.bought_in_condition,
-    //     media_condition: payload.media_condition,
-    //     sleeve_condition: payload.sleeve_condition,
-    //     digital_files_included: payload.digital_files_included,
-    // };
-
-    // return match store.create_vinyl(vinyl).await {
-    //     Ok(_) => (
-    //         StatusCode::OK,
-    //         CreateVinylResponse::success("Vinyl created"),
-    //     ),
-    //     Err(_) => (
-    //         StatusCode::INTERNAL_SERVER_ERROR,
-    //         CreateVinylResponse::error("Internal Server Error"),
-    //     ),
-    // };
+    list_media_entries(&store, query, filter, MediaSort::default()).await
 }
 
-pub async fn get_media_entries(Extension(store): Extension<Store>) -> impl IntoResponse {
-    let media_entries = match store.get_media_entries(0, 0).await {
-        Ok(media_entries) => media_entries,
-        Err(_) => todo!(),
+/// Validates the pagination and sort parameters in `query` and returns the matching page of media entries. If no sort
+/// key is requested, `default_sort` is used. This is shared by all endpoints which list media entries.
+async fn list_media_entries(
+    store: &Store,
+    query: GetMediaEntriesQuery,
+    filter: MediaFilter,
+    default_sort: MediaSort,
+) -> (StatusCode, GetMediaEntriesResponse) {
+    let sort = query.sort.unwrap_or(default_sort);
+
+    let limit = query.limit.unwrap_or(constants::DEFAULT_PAGE_LIMIT);
+    if !(1..=constants::MAX_PAGE_LIMIT).contains(&limit) {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetMediaEntriesResponse::error(format!(
+                "Limit must be between 1 and {}",
+                constants::MAX_PAGE_LIMIT
+            )),
+        );
+    }
+
+    let offset = query.offset.unwrap_or(0);
+    if offset < 0 {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetMediaEntriesResponse::error("Offset must not be negative"),
+        );
+    }
+
+    let cursor = match query.cursor.map(|c| c.parse::<MediaCursor>()).transpose() {
+        Ok(cursor) => cursor,
+        Err(err) => return (StatusCode::BAD_REQUEST, GetMediaEntriesResponse::error(err)),
     };
 
-    return (
-        StatusCode::OK,
-        GetMediaEntriesResponse::success(media_entries),
-    );
+    if let Some(cursor) = &cursor {
+        if !cursor.matches(sort, query.order) {
+            return (
+                StatusCode::BAD_REQUEST,
+                GetMediaEntriesResponse::error("Cursor does not match the requested sort order"),
+            );
+        }
+    }
+
+    match store
+        .get_media_entries(&filter, sort, query.order, limit, offset, cursor)
+        .await
+    {
+        Ok(mut page) => {
+            page.entries
+                .iter_mut()
+                .for_each(|entry| entry.set_scale(query.scale));
+            (StatusCode::OK, GetMediaEntriesResponse::success(page))
+        }
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            GetMediaEntriesResponse::error(err.to_string()),
+        ),
+    }
 }
 
 pub async fn get_media_entry(
     Path(media_id): Path<String>,
+    Query(scale): Query<GradingScaleQuery>,
     Extension(store): Extension<Store>,
 ) -> impl IntoResponse {
     if media_id.is_empty() {
@@ -70,20 +147,207 @@ pub async fn get_media_entry(
     }
 
     match store.get_media_entry(media_id).await {
-        Ok(media_entry) => return (StatusCode::OK, GetMediaEntryResponse::success(media_entry)),
-        Err(err) => {
-            return (
+        Ok(mut media_entry) => {
+            media_entry.set_scale(scale.scale);
+            (StatusCode::OK, GetMediaEntryResponse::success(media_entry))
+        }
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                GetMediaEntryResponse::error(err.to_string()),
+            ),
+            _ => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 GetMediaEntryResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+pub async fn update_media_entry(
+    Path(media_id): Path<String>,
+    Query(scale): Query<GradingScaleQuery>,
+    Extension(store): Extension<Store>,
+    Json(payload): Json<UpdateMediaRequest>,
+) -> impl IntoResponse {
+    if media_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            UpdateMediaEntryResponse::error("Invalid media ID"),
+        );
+    }
+
+    if let Some(title) = &payload.title {
+        if title.is_empty() {
+            return (
+                StatusCode::BAD_REQUEST,
+                UpdateMediaEntryResponse::error("Media title must not be empty"),
             );
         }
-    };
+    }
+
+    if let Some(release_id) = &payload.release_id {
+        if release_id.len() != constants::NANOID_LEN {
+            return (
+                StatusCode::BAD_REQUEST,
+                UpdateMediaEntryResponse::error("Invalid release ID"),
+            );
+        }
+    }
+
+    match store.update_media_entry(media_id, payload).await {
+        Ok(mut media_entry) => {
+            media_entry.set_scale(scale.scale);
+            (
+                StatusCode::OK,
+                UpdateMediaEntryResponse::success(media_entry),
+            )
+        }
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                UpdateMediaEntryResponse::error(err.to_string()),
+            ),
+            StoreErrorKind::Invalid => (
+                StatusCode::BAD_REQUEST,
+                UpdateMediaEntryResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                UpdateMediaEntryResponse::error(err.to_string()),
+            ),
+        },
+    }
 }
 
-pub async fn update_media_entry() -> impl IntoResponse {
-    return StatusCode::OK;
+pub async fn delete_media_entry(
+    Path(media_id): Path<String>,
+    Query(query): Query<DeleteMediaQuery>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if media_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            DeleteMediaEntryResponse::error("Invalid media ID"),
+        );
+    }
+
+    match store
+        .delete_media_entry(media_id, query.remove_orphans)
+        .await
+    {
+        Ok(_) => (StatusCode::OK, DeleteMediaEntryResponse::success()),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                DeleteMediaEntryResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                DeleteMediaEntryResponse::error(err.to_string()),
+            ),
+        },
+    }
 }
 
-pub async fn delete_media_entry() -> impl IntoResponse {
-    return StatusCode::OK;
+pub async fn search_media(
+    Query(query): Query<SearchQuery>,
+    Query(scale): Query<GradingScaleQuery>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if query.q.trim().is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            SearchResponse::error("Missing search query"),
+        );
+    }
+
+    let limit = query.limit.unwrap_or(constants::DEFAULT_PAGE_LIMIT);
+    if !(1..=constants::MAX_PAGE_LIMIT).contains(&limit) {
+        return (
+            StatusCode::BAD_REQUEST,
+            SearchResponse::error(format!(
+                "Limit must be between 1 and {}",
+                constants::MAX_PAGE_LIMIT
+            )),
+        );
+    }
+
+    match store.search_media(&query.q, limit).await {
+        Ok(mut results) => {
+            results
+                .iter_mut()
+                .for_each(|result| result.entry.set_scale(scale.scale));
+            (StatusCode::OK, SearchResponse::success(results))
+        }
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            SearchResponse::error(err.to_string()),
+        ),
+    }
+}
+
+pub async fn search_runouts(
+    Query(query): Query<RunoutSearchQuery>,
+    Query(scale): Query<GradingScaleQuery>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if !query.q.chars().any(|c| c.is_alphanumeric()) {
+        return (
+            StatusCode::BAD_REQUEST,
+            RunoutSearchResponse::error("Missing runout etching"),
+        );
+    }
+
+    let limit = query.limit.unwrap_or(constants::DEFAULT_PAGE_LIMIT);
+    if !(1..=constants::MAX_PAGE_LIMIT).contains(&limit) {
+        return (
+            StatusCode::BAD_REQUEST,
+            RunoutSearchResponse::error(format!(
+                "Limit must be between 1 and {}",
+                constants::MAX_PAGE_LIMIT
+            )),
+        );
+    }
+
+    match store.search_runouts(&query.q, limit).await {
+        Ok(mut results) => {
+            results
+                .iter_mut()
+                .for_each(|result| result.entry.set_scale(scale.scale));
+            (StatusCode::OK, RunoutSearchResponse::success(results))
+        }
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            RunoutSearchResponse::error(err.to_string()),
+        ),
+    }
+}
+
+/// Returns statistics about the whole collection, aggregated in the database.
+pub async fn get_stats(
+    Query(query): Query<StatsQuery>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    let top = query.top.unwrap_or(constants::DEFAULT_STATS_TOP);
+    if !(1..=constants::MAX_PAGE_LIMIT).contains(&top) {
+        return (
+            StatusCode::BAD_REQUEST,
+            StatsResponse::error(format!(
+                "Top must be between 1 and {}",
+                constants::MAX_PAGE_LIMIT
+            )),
+        );
+    }
+
+    match store.get_stats(top).await {
+        Ok(mut stats) => {
+            stats.set_scale(query.scale);
+            (StatusCode::OK, StatsResponse::success(stats))
+        }
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatsResponse::error(err.to_string()),
+        ),
+    }
 }
@@ -0,0 +1,150 @@
+use axum::{
+    extract::{Extension, Json, Path, Query},
+    http::StatusCode,
+    response::IntoResponse,
+};
+
+use crate::{
+    constants,
+    server::routes::list_media_entries,
+    store::{error::StoreErrorKind, Store},
+    types::{
+        CreatePlayRequest, CreatePlayResponse, DeletePlayResponse, GetMediaEntriesQuery,
+        GetPlaysQuery, GetPlaysResponse, MediaFilter, MediaSort,
+    },
+};
+
+/// Lists the listening log, most recent play first.
+pub async fn get_plays(
+    Query(query): Query<GetPlaysQuery>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    let limit = query.limit.unwrap_or(constants::DEFAULT_PAGE_LIMIT);
+    if !(1..=constants::MAX_PAGE_LIMIT).contains(&limit) {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetPlaysResponse::error(format!(
+                "Limit must be between 1 and {}",
+                constants::MAX_PAGE_LIMIT
+            )),
+        );
+    }
+
+    let offset = query.offset.unwrap_or(0);
+    if offset < 0 {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetPlaysResponse::error("Offset must not be negative"),
+        );
+    }
+
+    match store.get_plays(limit, offset).await {
+        Ok((plays, total)) => (StatusCode::OK, GetPlaysResponse::success(plays, total)),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            GetPlaysResponse::error(err.to_string()),
+        ),
+    }
+}
+
+/// Lists media entries by when they were last played, entries which were never played first. Supports the same
+/// pagination, sort and filter parameters as the media listing.
+pub async fn get_least_recently_played(
+    Query(query): Query<GetMediaEntriesQuery>,
+    Query(filter): Query<MediaFilter>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    list_media_entries(&store, query, filter, MediaSort::LastPlayed).await
+}
+
+pub async fn delete_play(
+    Path(play_id): Path<String>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if play_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            DeletePlayResponse::error("Invalid play ID"),
+        );
+    }
+
+    match store.delete_play(play_id).await {
+        Ok(_) => (StatusCode::OK, DeletePlayResponse::success()),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                DeletePlayResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                DeletePlayResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+/// Lists all logged plays of a media entry, most recent play first.
+pub async fn get_media_plays(
+    Path(media_id): Path<String>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if media_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetPlaysResponse::error("Invalid media ID"),
+        );
+    }
+
+    match store.get_media_plays(media_id).await {
+        Ok(plays) => {
+            let total = plays.len() as i64;
+            (StatusCode::OK, GetPlaysResponse::success(plays, total))
+        }
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                GetPlaysResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                GetPlaysResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+/// Logs a play of a media entry. The response carries a warning if the stylus the play was attributed to is wearing
+/// out.
+pub async fn create_play(
+    Path(media_id): Path<String>,
+    Extension(store): Extension<Store>,
+    Json(payload): Json<CreatePlayRequest>,
+) -> impl IntoResponse {
+    if media_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            CreatePlayResponse::error("Invalid media ID"),
+        );
+    }
+
+    match store.create_play(media_id, payload).await {
+        Ok((play, warning)) => (
+            StatusCode::CREATED,
+            CreatePlayResponse::success(play, warning),
+        ),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                CreatePlayResponse::error(err.to_string()),
+            ),
+            StoreErrorKind::Invalid => (
+                StatusCode::BAD_REQUEST,
+                CreatePlayResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                CreatePlayResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
@@ -0,0 +1,124 @@
+use axum::{
+    extract::{Extension, Json, Path, Query},
+    http::StatusCode,
+    response::IntoResponse,
+};
+
+use crate::{
+    constants,
+    store::{error::StoreErrorKind, Store},
+    types::{
+        CreateCopyRequest, CreateMediaEntryResponse, GetReleaseResponse, GetReleasesQuery,
+        GetReleasesResponse, GradingScaleQuery,
+    },
+};
+
+/// Lists the releases ordered by title, each with all of its copies.
+pub async fn get_releases(
+    Query(query): Query<GetReleasesQuery>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    let limit = query.limit.unwrap_or(constants::DEFAULT_PAGE_LIMIT);
+    if !(1..=constants::MAX_PAGE_LIMIT).contains(&limit) {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetReleasesResponse::error(format!(
+                "Limit must be between 1 and {}",
+                constants::MAX_PAGE_LIMIT
+            )),
+        );
+    }
+
+    let offset = query.offset.unwrap_or(0);
+    if offset < 0 {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetReleasesResponse::error("Offset must not be negative"),
+        );
+    }
+
+    match store.get_releases(limit, offset).await {
+        Ok((mut releases, total)) => {
+            releases
+                .iter_mut()
+                .for_each(|release| release.set_scale(query.scale));
+            (
+                StatusCode::OK,
+                GetReleasesResponse::success(releases, total),
+            )
+        }
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            GetReleasesResponse::error(err.to_string()),
+        ),
+    }
+}
+
+pub async fn get_release(
+    Path(release_id): Path<String>,
+    Query(scale): Query<GradingScaleQuery>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if release_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetReleaseResponse::error("Invalid release ID"),
+        );
+    }
+
+    match store.get_release(release_id).await {
+        Ok(mut release) => {
+            release.set_scale(scale.scale);
+            (StatusCode::OK, GetReleaseResponse::success(release))
+        }
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                GetReleaseResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                GetReleaseResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+/// Adds another owned copy of a release. The copy is returned as a media entry.
+pub async fn create_copy(
+    Path(release_id): Path<String>,
+    Query(scale): Query<GradingScaleQuery>,
+    Extension(store): Extension<Store>,
+    Json(payload): Json<CreateCopyRequest>,
+) -> impl IntoResponse {
+    if release_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            CreateMediaEntryResponse::error("Invalid release ID"),
+        );
+    }
+
+    match store.create_copy(release_id, payload).await {
+        Ok(mut media_entry) => {
+            media_entry.set_scale(scale.scale);
+            (
+                StatusCode::CREATED,
+                CreateMediaEntryResponse::success(media_entry),
+            )
+        }
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                CreateMediaEntryResponse::error(err.to_string()),
+            ),
+            StoreErrorKind::Invalid => (
+                StatusCode::BAD_REQUEST,
+                CreateMediaEntryResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                CreateMediaEntryResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
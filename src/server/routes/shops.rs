@@ -0,0 +1,203 @@
+use axum::{
+    extract::{Extension, Json, Path, Query},
+    http::StatusCode,
+    response::IntoResponse,
+};
+
+use crate::{
+    constants,
+    server::routes::list_media_entries,
+    store::{error::StoreErrorKind, Store},
+    types::{
+        CreateShopRequest, DeleteShopResponse, GetMediaEntriesQuery, GetMediaEntriesResponse,
+        GetShopsQuery, GetShopsResponse, MediaFilter, MediaSort, ShopResponse, UpdateShopRequest,
+    },
+};
+
+pub async fn get_shops(
+    Query(query): Query<GetShopsQuery>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    let limit = query.limit.unwrap_or(constants::DEFAULT_PAGE_LIMIT);
+    if !(1..=constants::MAX_PAGE_LIMIT).contains(&limit) {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetShopsResponse::error(format!(
+                "Limit must be between 1 and {}",
+                constants::MAX_PAGE_LIMIT
+            )),
+        );
+    }
+
+    let offset = query.offset.unwrap_or(0);
+    if offset < 0 {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetShopsResponse::error("Offset must not be negative"),
+        );
+    }
+
+    match store.get_shops(limit, offset).await {
+        Ok((shops, total)) => (StatusCode::OK, GetShopsResponse::success(shops, total)),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            GetShopsResponse::error(err.to_string()),
+        ),
+    }
+}
+
+pub async fn get_shop(
+    Path(shop_id): Path<String>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if shop_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            ShopResponse::error("Invalid shop ID"),
+        );
+    }
+
+    match store.get_shop(shop_id).await {
+        Ok(shop) => (StatusCode::OK, ShopResponse::success(shop)),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => {
+                (StatusCode::NOT_FOUND, ShopResponse::error(err.to_string()))
+            }
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ShopResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+pub async fn create_shop(
+    Extension(store): Extension<Store>,
+    Json(payload): Json<CreateShopRequest>,
+) -> impl IntoResponse {
+    if payload.name.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            ShopResponse::error("Missing shop name"),
+        );
+    }
+
+    match store.create_shop(payload).await {
+        Ok(shop) => (StatusCode::CREATED, ShopResponse::success(shop)),
+        Err(err) => match err.kind() {
+            StoreErrorKind::Invalid => (
+                StatusCode::BAD_REQUEST,
+                ShopResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ShopResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+pub async fn update_shop(
+    Path(shop_id): Path<String>,
+    Extension(store): Extension<Store>,
+    Json(payload): Json<UpdateShopRequest>,
+) -> impl IntoResponse {
+    if shop_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            ShopResponse::error("Invalid shop ID"),
+        );
+    }
+
+    if let Some(name) = &payload.name {
+        if name.is_empty() {
+            return (
+                StatusCode::BAD_REQUEST,
+                ShopResponse::error("Shop name must not be empty"),
+            );
+        }
+    }
+
+    match store.update_shop(shop_id, payload).await {
+        Ok(shop) => (StatusCode::OK, ShopResponse::success(shop)),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => {
+                (StatusCode::NOT_FOUND, ShopResponse::error(err.to_string()))
+            }
+            StoreErrorKind::Invalid => (
+                StatusCode::BAD_REQUEST,
+                ShopResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ShopResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+pub async fn delete_shop(
+    Path(shop_id): Path<String>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if shop_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            DeleteShopResponse::error("Invalid shop ID"),
+        );
+    }
+
+    match store.delete_shop(shop_id).await {
+        Ok(_) => (StatusCode::OK, DeleteShopResponse::success()),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                DeleteShopResponse::error(err.to_string()),
+            ),
+            StoreErrorKind::Conflict => (
+                StatusCode::CONFLICT,
+                DeleteShopResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                DeleteShopResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+/// Lists every media entry bought at the shop. Entries are sorted by purchase date unless another sort key is requested.
+/// Supports the same pagination, sort and filter parameters as the media listing.
+pub async fn get_shop_media_entries(
+    Path(shop_id): Path<String>,
+    Query(query): Query<GetMediaEntriesQuery>,
+    Query(filter): Query<MediaFilter>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if shop_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetMediaEntriesResponse::error("Invalid shop ID"),
+        );
+    }
+
+    if let Err(err) = store.get_shop(shop_id.clone()).await {
+        return match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                GetMediaEntriesResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                GetMediaEntriesResponse::error(err.to_string()),
+            ),
+        };
+    }
+
+    let filter = MediaFilter {
+        shop: Some(shop_id),
+        ..filter
+    };
+
+    list_media_entries(&store, query, filter, MediaSort::PurchaseDate).await
+}
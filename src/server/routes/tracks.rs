@@ -0,0 +1,195 @@
+use axum::{
+    extract::{Extension, Json, Path},
+    http::StatusCode,
+    response::IntoResponse,
+};
+
+use crate::{
+    constants,
+    store::{error::StoreErrorKind, Store},
+    types::{
+        CreateTrackRequest, DeleteTrackResponse, GetTracksResponse, TrackResponse,
+        UpdateTrackRequest,
+    },
+};
+
+pub async fn get_tracks(
+    Path(media_id): Path<String>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if media_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetTracksResponse::error("Invalid media ID"),
+        );
+    }
+
+    match store.get_tracks(media_id).await {
+        Ok(tracks) => (StatusCode::OK, GetTracksResponse::success(tracks)),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                GetTracksResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                GetTracksResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+/// Replaces all tracks of a media entry with the tracks in the request body.
+pub async fn replace_tracks(
+    Path(media_id): Path<String>,
+    Extension(store): Extension<Store>,
+    Json(payload): Json<Vec<CreateTrackRequest>>,
+) -> impl IntoResponse {
+    if media_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetTracksResponse::error("Invalid media ID"),
+        );
+    }
+
+    if payload.iter().any(|t| t.title.is_empty()) {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetTracksResponse::error("Missing track title"),
+        );
+    }
+
+    match store.replace_tracks(media_id, payload).await {
+        Ok(tracks) => (StatusCode::OK, GetTracksResponse::success(tracks)),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                GetTracksResponse::error(err.to_string()),
+            ),
+            StoreErrorKind::Invalid => (
+                StatusCode::BAD_REQUEST,
+                GetTracksResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                GetTracksResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+pub async fn add_track(
+    Path(media_id): Path<String>,
+    Extension(store): Extension<Store>,
+    Json(payload): Json<CreateTrackRequest>,
+) -> impl IntoResponse {
+    if media_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            TrackResponse::error("Invalid media ID"),
+        );
+    }
+
+    if payload.title.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            TrackResponse::error("Missing track title"),
+        );
+    }
+
+    match store.add_track(media_id, payload).await {
+        Ok(track) => (StatusCode::CREATED, TrackResponse::success(track)),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => {
+                (StatusCode::NOT_FOUND, TrackResponse::error(err.to_string()))
+            }
+            StoreErrorKind::Invalid => (
+                StatusCode::BAD_REQUEST,
+                TrackResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                TrackResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+pub async fn update_track(
+    Path((media_id, track_id)): Path<(String, String)>,
+    Extension(store): Extension<Store>,
+    Json(payload): Json<UpdateTrackRequest>,
+) -> impl IntoResponse {
+    if media_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            TrackResponse::error("Invalid media ID"),
+        );
+    }
+
+    if track_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            TrackResponse::error("Invalid track ID"),
+        );
+    }
+
+    if let Some(title) = &payload.title {
+        if title.is_empty() {
+            return (
+                StatusCode::BAD_REQUEST,
+                TrackResponse::error("Track title must not be empty"),
+            );
+        }
+    }
+
+    match store.update_track(media_id, track_id, payload).await {
+        Ok(track) => (StatusCode::OK, TrackResponse::success(track)),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => {
+                (StatusCode::NOT_FOUND, TrackResponse::error(err.to_string()))
+            }
+            StoreErrorKind::Invalid => (
+                StatusCode::BAD_REQUEST,
+                TrackResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                TrackResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+pub async fn delete_track(
+    Path((media_id, track_id)): Path<(String, String)>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if media_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            DeleteTrackResponse::error("Invalid media ID"),
+        );
+    }
+
+    if track_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            DeleteTrackResponse::error("Invalid track ID"),
+        );
+    }
+
+    match store.delete_track(media_id, track_id).await {
+        Ok(_) => (StatusCode::OK, DeleteTrackResponse::success()),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                DeleteTrackResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                DeleteTrackResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
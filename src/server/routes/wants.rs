@@ -0,0 +1,219 @@
+use axum::{
+    extract::{Extension, Json, Path, Query},
+    http::StatusCode,
+    response::IntoResponse,
+};
+
+use crate::{
+    constants,
+    store::{error::StoreErrorKind, Store},
+    types::{
+        ConvertWantRequest, CreateMediaEntryResponse, CreateWantRequest, DeleteWantResponse,
+        GetWantsQuery, GetWantsResponse, GradingScaleQuery, UpdateWantRequest, WantResponse,
+    },
+};
+
+/// Lists the wants, highest priority first.
+pub async fn get_wants(
+    Query(query): Query<GetWantsQuery>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    let limit = query.limit.unwrap_or(constants::DEFAULT_PAGE_LIMIT);
+    if !(1..=constants::MAX_PAGE_LIMIT).contains(&limit) {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetWantsResponse::error(format!(
+                "Limit must be between 1 and {}",
+                constants::MAX_PAGE_LIMIT
+            )),
+        );
+    }
+
+    let offset = query.offset.unwrap_or(0);
+    if offset < 0 {
+        return (
+            StatusCode::BAD_REQUEST,
+            GetWantsResponse::error("Offset must not be negative"),
+        );
+    }
+
+    match store.get_wants(limit, offset, query.priority).await {
+        Ok((mut wants, total)) => {
+            wants
+                .iter_mut()
+                .for_each(|want| want.set_scale(query.scale));
+            (StatusCode::OK, GetWantsResponse::success(wants, total))
+        }
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            GetWantsResponse::error(err.to_string()),
+        ),
+    }
+}
+
+pub async fn get_want(
+    Path(want_id): Path<String>,
+    Query(scale): Query<GradingScaleQuery>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if want_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            WantResponse::error("Invalid want ID"),
+        );
+    }
+
+    match store.get_want(want_id).await {
+        Ok(mut want) => {
+            want.set_scale(scale.scale);
+            (StatusCode::OK, WantResponse::success(want))
+        }
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => {
+                (StatusCode::NOT_FOUND, WantResponse::error(err.to_string()))
+            }
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                WantResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+pub async fn create_want(
+    Query(scale): Query<GradingScaleQuery>,
+    Extension(store): Extension<Store>,
+    Json(payload): Json<CreateWantRequest>,
+) -> impl IntoResponse {
+    if payload.title.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            WantResponse::error("Missing want title"),
+        );
+    }
+
+    match store.create_want(payload).await {
+        Ok(mut want) => {
+            want.set_scale(scale.scale);
+            (StatusCode::CREATED, WantResponse::success(want))
+        }
+        Err(err) => match err.kind() {
+            StoreErrorKind::Invalid => (
+                StatusCode::BAD_REQUEST,
+                WantResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                WantResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+pub async fn update_want(
+    Path(want_id): Path<String>,
+    Query(scale): Query<GradingScaleQuery>,
+    Extension(store): Extension<Store>,
+    Json(payload): Json<UpdateWantRequest>,
+) -> impl IntoResponse {
+    if want_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            WantResponse::error("Invalid want ID"),
+        );
+    }
+
+    if let Some(title) = &payload.title {
+        if title.is_empty() {
+            return (
+                StatusCode::BAD_REQUEST,
+                WantResponse::error("Want title must not be empty"),
+            );
+        }
+    }
+
+    match store.update_want(want_id, payload).await {
+        Ok(mut want) => {
+            want.set_scale(scale.scale);
+            (StatusCode::OK, WantResponse::success(want))
+        }
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => {
+                (StatusCode::NOT_FOUND, WantResponse::error(err.to_string()))
+            }
+            StoreErrorKind::Invalid => (
+                StatusCode::BAD_REQUEST,
+                WantResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                WantResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+pub async fn delete_want(
+    Path(want_id): Path<String>,
+    Extension(store): Extension<Store>,
+) -> impl IntoResponse {
+    if want_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            DeleteWantResponse::error("Invalid want ID"),
+        );
+    }
+
+    match store.delete_want(want_id).await {
+        Ok(_) => (StatusCode::OK, DeleteWantResponse::success()),
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                DeleteWantResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                DeleteWantResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
+
+/// Converts a bought want into a media entry. The want is removed and the created media entry is returned.
+pub async fn convert_want(
+    Path(want_id): Path<String>,
+    Query(scale): Query<GradingScaleQuery>,
+    Extension(store): Extension<Store>,
+    Json(payload): Json<ConvertWantRequest>,
+) -> impl IntoResponse {
+    if want_id.len() != constants::NANOID_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            CreateMediaEntryResponse::error("Invalid want ID"),
+        );
+    }
+
+    match store.convert_want(want_id, payload).await {
+        Ok(mut media_entry) => {
+            media_entry.set_scale(scale.scale);
+            (
+                StatusCode::CREATED,
+                CreateMediaEntryResponse::success(media_entry),
+            )
+        }
+        Err(err) => match err.kind() {
+            StoreErrorKind::NotFound => (
+                StatusCode::NOT_FOUND,
+                CreateMediaEntryResponse::error(err.to_string()),
+            ),
+            StoreErrorKind::Invalid => (
+                StatusCode::BAD_REQUEST,
+                CreateMediaEntryResponse::error(err.to_string()),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                CreateMediaEntryResponse::error(err.to_string()),
+            ),
+        },
+    }
+}
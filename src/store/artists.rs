@@ -0,0 +1,259 @@
+use nanoid::nanoid;
+use sqlx::{query, query_scalar};
+
+use crate::{
+    store::{error::StoreError, Store},
+    types::{Artist, CreateArtistRequest, LinkOwner, UpdateArtistRequest},
+};
+
+impl Store {
+    /// Returns a page of artists ordered by name together with the total number of artists.
+    pub async fn get_artists(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Artist>, i64), StoreError> {
+        let total = match query_scalar!(r#"SELECT COUNT(*) as "count!" FROM artists"#)
+            .fetch_one(&self.pool)
+            .await
+        {
+            Ok(total) => total,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to count artists in 'artists' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let rows = match query!(
+            r#"
+                SELECT id, name FROM artists
+                ORDER BY lower(name), id
+                LIMIT $1 OFFSET $2
+            "#,
+            limit,
+            offset
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(rows) => rows,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch artists from 'artists' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let artist_ids = rows.iter().map(|r| r.id.clone()).collect::<Vec<String>>();
+        let mut links = Self::fetch_links(&self.pool, LinkOwner::Artist, &artist_ids).await?;
+
+        let artists = rows
+            .into_iter()
+            .map(|r| Artist {
+                links: links.remove(&r.id).unwrap_or_default(),
+                id: r.id,
+                name: r.name,
+            })
+            .collect();
+
+        Ok((artists, total))
+    }
+
+    pub async fn get_artist(&self, id: String) -> Result<Artist, StoreError> {
+        let row = match query!("SELECT id, name FROM artists WHERE id = $1", id)
+            .fetch_one(&self.pool)
+            .await
+        {
+            Ok(row) => row,
+            Err(sqlx::Error::RowNotFound) => {
+                return Err(StoreError::not_found(format!(
+                    "Artist '{}' does not exist",
+                    id
+                )))
+            }
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch artist from 'artists' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let mut links =
+            Self::fetch_links(&self.pool, LinkOwner::Artist, std::slice::from_ref(&id)).await?;
+
+        Ok(Artist {
+            links: links.remove(&row.id).unwrap_or_default(),
+            id: row.id,
+            name: row.name,
+        })
+    }
+
+    pub async fn create_artist(&self, artist: CreateArtistRequest) -> Result<Artist, StoreError> {
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        let id = nanoid!();
+
+        if let Err(err) = query!(
+            "INSERT INTO artists (id, name) VALUES ($1, $2)",
+            id,
+            artist.name
+        )
+        .execute(&mut tx)
+        .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to insert into 'artists' table: {}",
+                err
+            )));
+        }
+
+        Self::set_links(&mut tx, LinkOwner::Artist, &id, artist.links).await?;
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        self.get_artist(id).await
+    }
+
+    /// Applies a sparse update to the artist with `id`. Fields which are not set in `artist` are left untouched.
+    pub async fn update_artist(
+        &self,
+        id: String,
+        artist: UpdateArtistRequest,
+    ) -> Result<Artist, StoreError> {
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        let result = match query!(
+            "UPDATE artists SET name = COALESCE($2, name) WHERE id = $1",
+            id,
+            artist.name
+        )
+        .execute(&mut tx)
+        .await
+        {
+            Ok(result) => result,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to update 'artists' table: {}",
+                    err
+                )))
+            }
+        };
+
+        if result.rows_affected() == 0 {
+            return Err(StoreError::not_found(format!(
+                "Artist '{}' does not exist",
+                id
+            )));
+        }
+
+        if let Some(links) = artist.links {
+            Self::set_links(&mut tx, LinkOwner::Artist, &id, links).await?;
+        }
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        self.get_artist(id).await
+    }
+
+    /// Deletes the artist with `id` and its links. Artists which still appear on media entries or wants can not
+    /// be deleted.
+    pub async fn delete_artist(&self, id: String) -> Result<(), StoreError> {
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        let in_use = match query_scalar!(
+            r#"
+                SELECT
+                    EXISTS(SELECT 1 FROM release_artists_rel WHERE artist_id = $1)
+                    OR EXISTS(SELECT 1 FROM wants_artists_rel WHERE artist_id = $1) as "exists!"
+            "#,
+            id
+        )
+        .fetch_one(&mut tx)
+        .await
+        {
+            Ok(in_use) => in_use,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to look up artist: {}",
+                    err
+                )))
+            }
+        };
+
+        if in_use {
+            return Err(StoreError::conflict(format!(
+                "Artist '{}' still appears on one or more media entries or wants",
+                id
+            )));
+        }
+
+        let result = match query!("DELETE FROM artists WHERE id = $1", id)
+            .execute(&mut tx)
+            .await
+        {
+            Ok(result) => result,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to delete from 'artists' table: {}",
+                    err
+                )))
+            }
+        };
+
+        if result.rows_affected() == 0 {
+            return Err(StoreError::not_found(format!(
+                "Artist '{}' does not exist",
+                id
+            )));
+        }
+
+        Self::delete_links(&mut tx, LinkOwner::Artist, &[id]).await?;
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,559 @@
+use nanoid::nanoid;
+use sqlx::{query, query_as, query_scalar, Postgres, Transaction};
+
+use crate::{
+    store::{
+        error::StoreError,
+        models::{ActiveEquipment, EquipmentJoin},
+        shops::normalize_text,
+        Store,
+    },
+    types::{
+        wear_warning, CreateEquipmentRequest, Equipment, EquipmentKind, UpdateEquipmentRequest,
+    },
+};
+
+/// Checks that only styli are mounted on a cartridge and have a lifetime, and that a stylus is mounted when it is
+/// active.
+fn validate_equipment(
+    kind: EquipmentKind,
+    cartridge_id: Option<&str>,
+    active: bool,
+    lifetime_hours: Option<i32>,
+) -> Result<(), StoreError> {
+    if kind != EquipmentKind::Stylus {
+        if cartridge_id.is_some() {
+            return Err(StoreError::invalid(
+                "Only styli can be mounted on a cartridge",
+            ));
+        }
+
+        if lifetime_hours.is_some() {
+            return Err(StoreError::invalid("Only styli have a lifetime"));
+        }
+
+        return Ok(());
+    }
+
+    if active && cartridge_id.is_none() {
+        return Err(StoreError::invalid(
+            "A stylus has to be mounted on a cartridge to be active",
+        ));
+    }
+
+    if lifetime_hours.is_some_and(|h| h <= 0) {
+        return Err(StoreError::invalid("Lifetime must be positive"));
+    }
+
+    Ok(())
+}
+
+impl Store {
+    /// Returns the IDs of the active turntable, the active cartridge and the stylus mounted on it.
+    pub(super) async fn active_equipment(
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> Result<ActiveEquipment, StoreError> {
+        match query_as!(
+            ActiveEquipment,
+            r#"
+                SELECT
+                    (SELECT id FROM equipment WHERE kind = 'Turntable' AND active) as turntable_id,
+                    (SELECT id FROM equipment WHERE kind = 'Cartridge' AND active) as cartridge_id,
+                    (
+                        SELECT styli.id FROM equipment styli
+                        JOIN equipment cartridges ON (cartridges.id = styli.cartridge_id)
+                        WHERE styli.kind = 'Stylus' AND styli.active AND cartridges.active
+                    ) as stylus_id
+            "#
+        )
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(active) => Ok(active),
+            Err(err) => Err(StoreError::new(format!(
+                "Failed to fetch active equipment from 'equipment' table: {}",
+                err
+            ))),
+        }
+    }
+
+    /// Returns the wear warning of the stylus with `stylus_id`, including plays logged in the same transaction.
+    pub(super) async fn stylus_warning(
+        tx: &mut Transaction<'_, Postgres>,
+        stylus_id: &str,
+    ) -> Result<Option<String>, StoreError> {
+        let stylus = match query!(
+            r#"
+                SELECT
+                    name, lifetime_hours,
+                    COALESCE((SELECT SUM(duration) FROM plays WHERE stylus_id = $1), 0)::BIGINT as "played_seconds!"
+                FROM equipment WHERE id = $1
+            "#,
+            stylus_id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(stylus) => stylus,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch equipment from 'equipment' table: {}",
+                    err
+                )))
+            }
+        };
+
+        Ok(wear_warning(
+            &stylus.name,
+            stylus.lifetime_hours,
+            stylus.played_seconds,
+        ))
+    }
+
+    /// Locks the cartridge with `cartridge_id` for the rest of the transaction. Returns an invalid error if it does not
+    /// exist or is no cartridge.
+    async fn lock_cartridge(
+        tx: &mut Transaction<'_, Postgres>,
+        cartridge_id: &str,
+    ) -> Result<(), StoreError> {
+        let kind = match query_scalar!(
+            r#"SELECT kind as "kind: EquipmentKind" FROM equipment WHERE id = $1 FOR UPDATE"#,
+            cartridge_id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        {
+            Ok(kind) => kind,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch equipment from 'equipment' table: {}",
+                    err
+                )))
+            }
+        };
+
+        match kind {
+            Some(EquipmentKind::Cartridge) => Ok(()),
+            Some(_) => Err(StoreError::invalid(format!(
+                "Equipment '{}' is not a cartridge",
+                cartridge_id
+            ))),
+            None => Err(StoreError::invalid(format!(
+                "Cartridge '{}' does not exist",
+                cartridge_id
+            ))),
+        }
+    }
+
+    /// Deactivates the equipment which is in the way of activating the equipment with `id`, i.e. the other active
+    /// equipment of the same kind, or for a stylus the other stylus mounted on `cartridge_id`.
+    async fn deactivate_others(
+        tx: &mut Transaction<'_, Postgres>,
+        id: &str,
+        kind: EquipmentKind,
+        cartridge_id: Option<&str>,
+    ) -> Result<(), StoreError> {
+        if let Err(err) = query!(
+            r#"
+                UPDATE equipment SET active = FALSE, modified_at = $4
+                WHERE active AND id != $1 AND kind = $2 AND (kind != 'Stylus' OR cartridge_id = $3)
+            "#,
+            id,
+            kind as _,
+            cartridge_id,
+            Self::now()
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to update 'equipment' table: {}",
+                err
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Returns a page of equipment together with the total number of matching equipment. Active equipment is listed
+    /// first within each kind.
+    pub async fn get_equipment_list(
+        &self,
+        limit: i64,
+        offset: i64,
+        kind: Option<EquipmentKind>,
+        active: Option<bool>,
+    ) -> Result<(Vec<Equipment>, i64), StoreError> {
+        let total = match query_scalar!(
+            r#"
+                SELECT COUNT(*) as "count!" FROM equipment
+                WHERE ($1::VARCHAR IS NULL OR kind = $1) AND ($2::BOOLEAN IS NULL OR active = $2)
+            "#,
+            kind as _,
+            active
+        )
+        .fetch_one(&self.pool)
+        .await
+        {
+            Ok(total) => total,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to count equipment in 'equipment' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let equipment = match query_as!(
+            EquipmentJoin,
+            r#"
+                SELECT
+                    equipment.id as equipment_id, equipment.kind as "equipment_kind: _",
+                    equipment.name as equipment_name, equipment.manufacturer as equipment_manufacturer,
+                    equipment.model as equipment_model, equipment.cartridge_id as equipment_cartridge_id,
+                    equipment.active as equipment_active, equipment.lifetime_hours as equipment_lifetime_hours,
+                    COUNT(plays.id) as "equipment_play_count!",
+                    COALESCE(SUM(plays.duration), 0)::BIGINT as "equipment_played_seconds!",
+                    equipment.notes as equipment_notes, equipment.created_at as equipment_created_at,
+                    equipment.modified_at as equipment_modified_at
+                FROM equipment
+                LEFT JOIN plays ON (
+                    plays.turntable_id = equipment.id OR plays.cartridge_id = equipment.id
+                    OR plays.stylus_id = equipment.id
+                )
+                WHERE ($1::VARCHAR IS NULL OR equipment.kind = $1) AND ($2::BOOLEAN IS NULL OR equipment.active = $2)
+                GROUP BY equipment.id
+                ORDER BY
+                    CASE equipment.kind WHEN 'Turntable' THEN 0 WHEN 'Cartridge' THEN 1 ELSE 2 END,
+                    equipment.active DESC, lower(equipment.name), equipment.id
+                LIMIT $3 OFFSET $4
+            "#,
+            kind as _,
+            active,
+            limit,
+            offset
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(equipment) => equipment,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch equipment from 'equipment' table: {}",
+                    err
+                )))
+            }
+        };
+
+        Ok((equipment.into_iter().map(Equipment::from).collect(), total))
+    }
+
+    pub async fn get_equipment(&self, id: String) -> Result<Equipment, StoreError> {
+        match query_as!(
+            EquipmentJoin,
+            r#"
+                SELECT
+                    equipment.id as equipment_id, equipment.kind as "equipment_kind: _",
+                    equipment.name as equipment_name, equipment.manufacturer as equipment_manufacturer,
+                    equipment.model as equipment_model, equipment.cartridge_id as equipment_cartridge_id,
+                    equipment.active as equipment_active, equipment.lifetime_hours as equipment_lifetime_hours,
+                    COUNT(plays.id) as "equipment_play_count!",
+                    COALESCE(SUM(plays.duration), 0)::BIGINT as "equipment_played_seconds!",
+                    equipment.notes as equipment_notes, equipment.created_at as equipment_created_at,
+                    equipment.modified_at as equipment_modified_at
+                FROM equipment
+                LEFT JOIN plays ON (
+                    plays.turntable_id = equipment.id OR plays.cartridge_id = equipment.id
+                    OR plays.stylus_id = equipment.id
+                )
+                WHERE equipment.id = $1
+                GROUP BY equipment.id
+            "#,
+            id
+        )
+        .fetch_one(&self.pool)
+        .await
+        {
+            Ok(equipment) => Ok(Equipment::from(equipment)),
+            Err(sqlx::Error::RowNotFound) => Err(StoreError::not_found(format!(
+                "Equipment '{}' does not exist",
+                id
+            ))),
+            Err(err) => Err(StoreError::new(format!(
+                "Failed to fetch equipment from 'equipment' table: {}",
+                err
+            ))),
+        }
+    }
+
+    pub async fn create_equipment(
+        &self,
+        equipment: CreateEquipmentRequest,
+    ) -> Result<Equipment, StoreError> {
+        let name = equipment.name.trim().to_string();
+        if name.is_empty() {
+            return Err(StoreError::invalid("Missing equipment name"));
+        }
+
+        validate_equipment(
+            equipment.kind,
+            equipment.cartridge_id.as_deref(),
+            equipment.active,
+            equipment.lifetime_hours,
+        )?;
+
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        if let Some(cartridge_id) = &equipment.cartridge_id {
+            Self::lock_cartridge(&mut tx, cartridge_id).await?;
+        }
+
+        let id = nanoid!();
+        if equipment.active {
+            Self::deactivate_others(
+                &mut tx,
+                &id,
+                equipment.kind,
+                equipment.cartridge_id.as_deref(),
+            )
+            .await?;
+        }
+
+        let now = Self::now();
+        if let Err(err) = query!(
+            r#"
+                INSERT INTO equipment (
+                    id, kind, name, manufacturer, model, cartridge_id, active, lifetime_hours, notes, created_at,
+                    modified_at
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            "#,
+            id,
+            equipment.kind as _,
+            name,
+            normalize_text(equipment.manufacturer),
+            normalize_text(equipment.model),
+            equipment.cartridge_id,
+            equipment.active,
+            equipment.lifetime_hours,
+            equipment.notes,
+            now,
+            now
+        )
+        .execute(&mut tx)
+        .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to insert into 'equipment' table: {}",
+                err
+            )));
+        }
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        self.get_equipment(id).await
+    }
+
+    /// Applies a sparse update to the equipment with `id`. Activating it deactivates the equipment in its way.
+    pub async fn update_equipment(
+        &self,
+        id: String,
+        equipment: UpdateEquipmentRequest,
+    ) -> Result<Equipment, StoreError> {
+        let name = equipment.name.map(|n| n.trim().to_string());
+        if name.as_deref() == Some("") {
+            return Err(StoreError::invalid("Equipment name must not be empty"));
+        }
+
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        let current = match query!(
+            r#"
+                SELECT kind as "kind: EquipmentKind", cartridge_id, active, lifetime_hours
+                FROM equipment WHERE id = $1 FOR UPDATE
+            "#,
+            id
+        )
+        .fetch_one(&mut tx)
+        .await
+        {
+            Ok(current) => current,
+            Err(sqlx::Error::RowNotFound) => {
+                return Err(StoreError::not_found(format!(
+                    "Equipment '{}' does not exist",
+                    id
+                )))
+            }
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch equipment from 'equipment' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let cartridge_changed = equipment.cartridge_id.is_some();
+        let cartridge_id = equipment.cartridge_id.unwrap_or(current.cartridge_id);
+        let active = equipment.active.unwrap_or(current.active);
+        let lifetime_hours = equipment.lifetime_hours.unwrap_or(current.lifetime_hours);
+        validate_equipment(
+            current.kind,
+            cartridge_id.as_deref(),
+            active,
+            lifetime_hours,
+        )?;
+
+        if cartridge_changed {
+            if let Some(cartridge_id) = &cartridge_id {
+                Self::lock_cartridge(&mut tx, cartridge_id).await?;
+            }
+        }
+
+        if active {
+            Self::deactivate_others(&mut tx, &id, current.kind, cartridge_id.as_deref()).await?;
+        }
+
+        if let Err(err) = query!(
+            r#"
+                UPDATE equipment SET
+                    name = COALESCE($2, name),
+                    manufacturer = CASE WHEN $3 THEN $4 ELSE manufacturer END,
+                    model = CASE WHEN $5 THEN $6 ELSE model END,
+                    cartridge_id = $7,
+                    active = $8,
+                    lifetime_hours = $9,
+                    notes = COALESCE($10, notes),
+                    modified_at = $11
+                WHERE id = $1
+            "#,
+            id,
+            name,
+            equipment.manufacturer.is_some(),
+            normalize_text(equipment.manufacturer.flatten()),
+            equipment.model.is_some(),
+            normalize_text(equipment.model.flatten()),
+            cartridge_id,
+            active,
+            lifetime_hours,
+            equipment.notes,
+            Self::now()
+        )
+        .execute(&mut tx)
+        .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to update 'equipment' table: {}",
+                err
+            )));
+        }
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        self.get_equipment(id).await
+    }
+
+    /// Deletes the equipment with `id`. Equipment which plays were attributed to or which styli are mounted on cannot
+    /// be deleted, it should be deactivated instead.
+    pub async fn delete_equipment(&self, id: String) -> Result<(), StoreError> {
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        let in_use = match query!(
+            r#"
+                SELECT
+                    EXISTS(
+                        SELECT 1 FROM plays WHERE turntable_id = $1 OR cartridge_id = $1 OR stylus_id = $1
+                    ) as "played!",
+                    EXISTS(SELECT 1 FROM equipment WHERE cartridge_id = $1) as "mounted!"
+            "#,
+            id
+        )
+        .fetch_one(&mut tx)
+        .await
+        {
+            Ok(in_use) => in_use,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to look up equipment: {}",
+                    err
+                )))
+            }
+        };
+
+        if in_use.played {
+            return Err(StoreError::conflict(format!(
+                "Plays were logged with equipment '{}'",
+                id
+            )));
+        }
+
+        if in_use.mounted {
+            return Err(StoreError::conflict(format!(
+                "Styli are mounted on cartridge '{}'",
+                id
+            )));
+        }
+
+        let result = match query!("DELETE FROM equipment WHERE id = $1", id)
+            .execute(&mut tx)
+            .await
+        {
+            Ok(result) => result,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to delete from 'equipment' table: {}",
+                    err
+                )))
+            }
+        };
+
+        if result.rows_affected() == 0 {
+            return Err(StoreError::not_found(format!(
+                "Equipment '{}' does not exist",
+                id
+            )));
+        }
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        Ok(())
+    }
+}
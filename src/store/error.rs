@@ -1,14 +1,56 @@
 use std::fmt;
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StoreErrorKind {
+    /// A generic database or internal failure.
+    Internal,
+
+    /// The requested entity does not exist.
+    NotFound,
+
+    /// The provided data is invalid, e.g. it references entities which do not exist.
+    Invalid,
+
+    /// The operation conflicts with the current state, e.g. deleting an entity which is still in use.
+    Conflict,
+}
+
 pub struct StoreError {
+    kind: StoreErrorKind,
     message: String,
 }
 
 impl StoreError {
     pub fn new<M: Into<String>>(message: M) -> Self {
-        return Self {
+        Self {
+            kind: StoreErrorKind::Internal,
+            message: message.into(),
+        }
+    }
+
+    pub fn not_found<M: Into<String>>(message: M) -> Self {
+        Self {
+            kind: StoreErrorKind::NotFound,
             message: message.into(),
-        };
+        }
+    }
+
+    pub fn invalid<M: Into<String>>(message: M) -> Self {
+        Self {
+            kind: StoreErrorKind::Invalid,
+            message: message.into(),
+        }
+    }
+
+    pub fn conflict<M: Into<String>>(message: M) -> Self {
+        Self {
+            kind: StoreErrorKind::Conflict,
+            message: message.into(),
+        }
+    }
+
+    pub fn kind(&self) -> StoreErrorKind {
+        self.kind
     }
 }
 
@@ -21,6 +63,7 @@ impl fmt::Display for StoreError {
 impl fmt::Debug for StoreError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("StoreError")
+            .field("kind", &self.kind)
             .field("message", &self.message)
             .finish()
     }
@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+
+use sqlx::{query, query_as, query_scalar, Executor, Postgres, Transaction};
+
+use crate::{
+    store::{error::StoreError, models::GradingsJoin, Store},
+    types::{ConditionGrading, CreateGradingRequest},
+};
+
+impl Store {
+    /// Records the current media and sleeve condition of the media entry with `media_id` as a new grading.
+    pub(super) async fn record_current_grading(
+        tx: &mut Transaction<'_, Postgres>,
+        media_id: &str,
+    ) -> Result<(), StoreError> {
+        if let Err(err) = query!(
+            r#"
+                INSERT INTO condition_gradings (media_id, graded_at, media_condition, sleeve_condition)
+                SELECT id, $2, media_condition, sleeve_condition FROM media WHERE id = $1
+            "#,
+            media_id,
+            Self::now()
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to insert into 'condition_gradings' table: {}",
+                err
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Deletes all gradings of the media entry with `media_id`.
+    pub(super) async fn delete_gradings(
+        tx: &mut Transaction<'_, Postgres>,
+        media_id: &str,
+    ) -> Result<(), StoreError> {
+        if let Err(err) = query!(
+            "DELETE FROM condition_gradings WHERE media_id = $1",
+            media_id
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to delete from 'condition_gradings' table: {}",
+                err
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the latest grading of each of the media entries with `media_ids`.
+    pub(super) async fn fetch_latest_gradings<'e, E>(
+        executor: E,
+        media_ids: &[String],
+    ) -> Result<HashMap<String, ConditionGrading>, StoreError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let gradings = match query_as!(
+            GradingsJoin,
+            r#"
+                SELECT DISTINCT ON (media_id)
+                    id as grading_id, media_id, graded_at as grading_graded_at, grader as grading_grader,
+                    media_condition as "grading_media_condition: _", sleeve_condition as "grading_sleeve_condition: _",
+                    notes as grading_notes
+                FROM condition_gradings
+                WHERE media_id = ANY($1)
+                ORDER BY media_id, graded_at DESC, id DESC
+            "#,
+            media_ids
+        )
+        .fetch_all(executor)
+        .await
+        {
+            Ok(gradings) => gradings,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch gradings from 'condition_gradings' table: {}",
+                    err
+                )))
+            }
+        };
+
+        Ok(gradings
+            .into_iter()
+            .map(|g| (g.media_id.clone(), ConditionGrading::from(g)))
+            .collect())
+    }
+
+    /// Returns the grading history of the media entry with `media_id`, oldest grading first.
+    pub async fn get_gradings(
+        &self,
+        media_id: String,
+    ) -> Result<Vec<ConditionGrading>, StoreError> {
+        let exists = match query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM media WHERE id = $1) as "exists!""#,
+            media_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        {
+            Ok(exists) => exists,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to look up media entry: {}",
+                    err
+                )))
+            }
+        };
+
+        if !exists {
+            return Err(StoreError::not_found(format!(
+                "Media entry '{}' does not exist",
+                media_id
+            )));
+        }
+
+        let gradings = match query_as!(
+            GradingsJoin,
+            r#"
+                SELECT
+                    id as grading_id, media_id, graded_at as grading_graded_at, grader as grading_grader,
+                    media_condition as "grading_media_condition: _", sleeve_condition as "grading_sleeve_condition: _",
+                    notes as grading_notes
+                FROM condition_gradings
+                WHERE media_id = $1
+                ORDER BY graded_at, id
+            "#,
+            media_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(gradings) => gradings,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch gradings from 'condition_gradings' table: {}",
+                    err
+                )))
+            }
+        };
+
+        Ok(gradings.into_iter().map(ConditionGrading::from).collect())
+    }
+
+    /// Adds a grading to the history of the media entry with `media_id`. The media and sleeve condition of the entry
+    /// always reflect its latest grading, so they are updated if the new grading is the latest one.
+    pub async fn add_grading(
+        &self,
+        media_id: String,
+        grading: CreateGradingRequest,
+    ) -> Result<ConditionGrading, StoreError> {
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        Self::touch_media(&mut tx, &media_id).await?;
+
+        let grader = grading
+            .grader
+            .map(|g| g.trim().to_string())
+            .filter(|g| !g.is_empty());
+
+        let grading = match query_as!(
+            GradingsJoin,
+            r#"
+                INSERT INTO condition_gradings (media_id, graded_at, grader, media_condition, sleeve_condition, notes)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                RETURNING
+                    id as grading_id, media_id, graded_at as grading_graded_at, grader as grading_grader,
+                    media_condition as "grading_media_condition: _", sleeve_condition as "grading_sleeve_condition: _",
+                    notes as grading_notes
+            "#,
+            media_id,
+            grading.graded_at.unwrap_or_else(Self::now),
+            grader,
+            grading.media_condition as _,
+            grading.sleeve_condition as _,
+            grading.notes
+        )
+        .fetch_one(&mut tx)
+        .await
+        {
+            Ok(grading) => grading,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to insert into 'condition_gradings' table: {}",
+                    err
+                )))
+            }
+        };
+
+        if let Err(err) = query!(
+            r#"
+                UPDATE media SET media_condition = latest.media_condition, sleeve_condition = latest.sleeve_condition
+                FROM (
+                    SELECT media_condition, sleeve_condition FROM condition_gradings
+                    WHERE media_id = $1
+                    ORDER BY graded_at DESC, id DESC
+                    LIMIT 1
+                ) AS latest
+                WHERE media.id = $1
+            "#,
+            media_id
+        )
+        .execute(&mut tx)
+        .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to update 'media' table: {}",
+                err
+            )));
+        }
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        Ok(ConditionGrading::from(grading))
+    }
+}
@@ -0,0 +1,278 @@
+use nanoid::nanoid;
+use sqlx::{query, query_scalar};
+
+use crate::{
+    store::{error::StoreError, Store},
+    types::{CreateLabelRequest, Label, LinkOwner, UpdateLabelRequest},
+};
+
+impl Store {
+    /// Returns a page of labels ordered by name together with the total number of labels.
+    pub async fn get_labels(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Label>, i64), StoreError> {
+        let total = match query_scalar!(r#"SELECT COUNT(*) as "count!" FROM labels"#)
+            .fetch_one(&self.pool)
+            .await
+        {
+            Ok(total) => total,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to count labels in 'labels' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let rows = match query!(
+            r#"
+                SELECT id, name, label_code FROM labels
+                ORDER BY lower(name), id
+                LIMIT $1 OFFSET $2
+            "#,
+            limit,
+            offset
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(rows) => rows,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch labels from 'labels' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let label_ids = rows.iter().map(|r| r.id.clone()).collect::<Vec<String>>();
+        let mut links = Self::fetch_links(&self.pool, LinkOwner::Label, &label_ids).await?;
+
+        let labels = rows
+            .into_iter()
+            .map(|r| Label {
+                links: links.remove(&r.id).unwrap_or_default(),
+                id: r.id,
+                name: r.name,
+                label_code: r.label_code,
+            })
+            .collect();
+
+        Ok((labels, total))
+    }
+
+    pub async fn get_label(&self, id: String) -> Result<Label, StoreError> {
+        let row = match query!("SELECT id, name, label_code FROM labels WHERE id = $1", id)
+            .fetch_one(&self.pool)
+            .await
+        {
+            Ok(row) => row,
+            Err(sqlx::Error::RowNotFound) => {
+                return Err(StoreError::not_found(format!(
+                    "Label '{}' does not exist",
+                    id
+                )))
+            }
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch label from 'labels' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let mut links =
+            Self::fetch_links(&self.pool, LinkOwner::Label, std::slice::from_ref(&id)).await?;
+
+        Ok(Label {
+            links: links.remove(&row.id).unwrap_or_default(),
+            id: row.id,
+            name: row.name,
+            label_code: row.label_code,
+        })
+    }
+
+    /// Creates a new label. The label code is validated and normalized, see [`Label::normalize_label_code`].
+    pub async fn create_label(&self, label: CreateLabelRequest) -> Result<Label, StoreError> {
+        let label_code = match Label::normalize_label_code(&label.label_code) {
+            Ok(label_code) => label_code,
+            Err(err) => return Err(StoreError::invalid(err)),
+        };
+
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        let id = nanoid!();
+
+        if let Err(err) = query!(
+            "INSERT INTO labels (id, name, label_code) VALUES ($1, $2, $3)",
+            id,
+            label.name,
+            label_code
+        )
+        .execute(&mut tx)
+        .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to insert into 'labels' table: {}",
+                err
+            )));
+        }
+
+        Self::set_links(&mut tx, LinkOwner::Label, &id, label.links).await?;
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        self.get_label(id).await
+    }
+
+    /// Applies a sparse update to the label with `id`. Fields which are not set in `label` are left untouched.
+    pub async fn update_label(
+        &self,
+        id: String,
+        label: UpdateLabelRequest,
+    ) -> Result<Label, StoreError> {
+        let label_code = match label
+            .label_code
+            .map(|c| Label::normalize_label_code(&c))
+            .transpose()
+        {
+            Ok(label_code) => label_code,
+            Err(err) => return Err(StoreError::invalid(err)),
+        };
+
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        let result = match query!(
+            r#"
+                UPDATE labels SET
+                    name = COALESCE($2, name),
+                    label_code = COALESCE($3, label_code)
+                WHERE id = $1
+            "#,
+            id,
+            label.name,
+            label_code
+        )
+        .execute(&mut tx)
+        .await
+        {
+            Ok(result) => result,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to update 'labels' table: {}",
+                    err
+                )))
+            }
+        };
+
+        if result.rows_affected() == 0 {
+            return Err(StoreError::not_found(format!(
+                "Label '{}' does not exist",
+                id
+            )));
+        }
+
+        if let Some(links) = label.links {
+            Self::set_links(&mut tx, LinkOwner::Label, &id, links).await?;
+        }
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        self.get_label(id).await
+    }
+
+    /// Deletes the label with `id` and its links. Labels still linked to media entries or wants can not be
+    /// deleted.
+    pub async fn delete_label(&self, id: String) -> Result<(), StoreError> {
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        let in_use = match query_scalar!(
+            r#"
+                SELECT
+                    EXISTS(SELECT 1 FROM release_label_rel WHERE label_id = $1)
+                    OR EXISTS(SELECT 1 FROM wants_label_rel WHERE label_id = $1) as "exists!"
+            "#,
+            id
+        )
+        .fetch_one(&mut tx)
+        .await
+        {
+            Ok(in_use) => in_use,
+            Err(err) => return Err(StoreError::new(format!("Failed to look up label: {}", err))),
+        };
+
+        if in_use {
+            return Err(StoreError::conflict(format!(
+                "Label '{}' is still linked to one or more media entries or wants",
+                id
+            )));
+        }
+
+        let result = match query!("DELETE FROM labels WHERE id = $1", id)
+            .execute(&mut tx)
+            .await
+        {
+            Ok(result) => result,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to delete from 'labels' table: {}",
+                    err
+                )))
+            }
+        };
+
+        if result.rows_affected() == 0 {
+            return Err(StoreError::not_found(format!(
+                "Label '{}' does not exist",
+                id
+            )));
+        }
+
+        Self::delete_links(&mut tx, LinkOwner::Label, &[id]).await?;
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use sqlx::{query, query_as, Executor, Postgres, Transaction};
+
+use crate::{
+    store::{error::StoreError, models::LinksJoin, Store},
+    types::{Link, LinkKind, LinkOwner, LinkRequest},
+};
+
+impl Store {
+    /// Replaces the links of the `owner` with `owner_id` by `links` and returns the stored links. The links are
+    /// validated and deduplicated first, invalid URLs result in an invalid error.
+    pub(super) async fn set_links(
+        tx: &mut Transaction<'_, Postgres>,
+        owner: LinkOwner,
+        owner_id: &str,
+        links: Vec<LinkRequest>,
+    ) -> Result<Vec<Link>, StoreError> {
+        let links = match Link::normalize_links(links) {
+            Ok(links) => links,
+            Err(err) => return Err(StoreError::invalid(err)),
+        };
+
+        Self::delete_links(tx, owner, &[owner_id.to_string()]).await?;
+
+        for link in &links {
+            if let Err(err) = query!(
+                "INSERT INTO links (owner, owner_id, kind, url) VALUES ($1, $2, $3, $4)",
+                owner as _,
+                owner_id,
+                link.kind as _,
+                link.url
+            )
+            .execute(&mut *tx)
+            .await
+            {
+                return Err(StoreError::new(format!(
+                    "Failed to insert into 'links' table: {}",
+                    err
+                )));
+            }
+        }
+
+        Ok(links)
+    }
+
+    /// Deletes all links of the `owner`s with `owner_ids`.
+    pub(super) async fn delete_links(
+        tx: &mut Transaction<'_, Postgres>,
+        owner: LinkOwner,
+        owner_ids: &[String],
+    ) -> Result<(), StoreError> {
+        if let Err(err) = query!(
+            "DELETE FROM links WHERE owner = $1 AND owner_id = ANY($2)",
+            owner as _,
+            owner_ids
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to delete from 'links' table: {}",
+                err
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the links of the `owner`s with `owner_ids`, grouped by owner ID. Links keep the order they were added
+    /// in.
+    pub(super) async fn fetch_links<'e, E>(
+        executor: E,
+        owner: LinkOwner,
+        owner_ids: &[String],
+    ) -> Result<HashMap<String, Vec<Link>>, StoreError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let links = match query_as!(
+            LinksJoin,
+            r#"
+                SELECT owner_id, kind as "link_kind: LinkKind", url as link_url FROM links
+                WHERE owner = $1 AND owner_id = ANY($2)
+                ORDER BY id
+            "#,
+            owner as _,
+            owner_ids
+        )
+        .fetch_all(executor)
+        .await
+        {
+            Ok(links) => links,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch links from 'links' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let mut map: HashMap<String, Vec<Link>> = HashMap::new();
+        for link in links {
+            map.entry(link.owner_id).or_default().push(Link {
+                kind: link.link_kind,
+                url: link.link_url,
+            });
+        }
+
+        Ok(map)
+    }
+}
@@ -0,0 +1,498 @@
+use std::collections::HashMap;
+
+use nanoid::nanoid;
+use sqlx::{
+    query, query_as, query_scalar,
+    types::time::{Date, OffsetDateTime},
+    Executor, Postgres, Transaction,
+};
+
+use crate::{
+    store::{error::StoreError, models::LoansJoin, shops::normalize_text, Store},
+    types::{CreateLoanRequest, Loan, UpdateLoanRequest},
+};
+
+/// Checks that a loan is neither due nor returned before it was lent.
+fn validate_loan_dates(
+    loan_date: Date,
+    due_date: Option<Date>,
+    return_date: Option<Date>,
+) -> Result<(), StoreError> {
+    if due_date.is_some_and(|d| d < loan_date) {
+        return Err(StoreError::invalid(
+            "Due date must not be before the loan date",
+        ));
+    }
+
+    if return_date.is_some_and(|d| d < loan_date) {
+        return Err(StoreError::invalid(
+            "Return date must not be before the loan date",
+        ));
+    }
+
+    Ok(())
+}
+
+impl Store {
+    /// Returns the open loan of each of the media entries with `media_ids` which is currently lent out.
+    pub(super) async fn fetch_open_loans<'e, E>(
+        executor: E,
+        media_ids: &[String],
+    ) -> Result<HashMap<String, Loan>, StoreError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let loans = match query_as!(
+            LoansJoin,
+            r#"
+                SELECT
+                    loans.id as loan_id, loans.media_id, releases.title as media_title, loans.borrower as loan_borrower,
+                    loans.contact as loan_contact, loans.loan_date as loan_loan_date, loans.due_date as loan_due_date,
+                    loans.return_date as loan_return_date, loans.notes as loan_notes,
+                    loans.created_at as loan_created_at, loans.modified_at as loan_modified_at
+                FROM loans
+                JOIN media ON (media.id = loans.media_id)
+                JOIN releases ON (releases.id = media.release_id)
+                WHERE loans.media_id = ANY($1) AND loans.return_date IS NULL
+            "#,
+            media_ids
+        )
+        .fetch_all(executor)
+        .await
+        {
+            Ok(loans) => loans,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch loans from 'loans' table: {}",
+                    err
+                )))
+            }
+        };
+
+        Ok(loans
+            .into_iter()
+            .map(|l| (l.media_id.clone(), Loan::from(l)))
+            .collect())
+    }
+
+    /// Deletes the whole loan history of the media entry with `media_id`.
+    pub(super) async fn delete_loans(
+        tx: &mut Transaction<'_, Postgres>,
+        media_id: &str,
+    ) -> Result<(), StoreError> {
+        if let Err(err) = query!("DELETE FROM loans WHERE media_id = $1", media_id)
+            .execute(&mut *tx)
+            .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to delete from 'loans' table: {}",
+                err
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Returns a page of loans, most recent first, together with the total number of matching loans. If `open` is
+    /// set, only open or only returned loans are listed.
+    pub async fn get_loans(
+        &self,
+        limit: i64,
+        offset: i64,
+        open: Option<bool>,
+    ) -> Result<(Vec<Loan>, i64), StoreError> {
+        let total = match query_scalar!(
+            r#"
+                SELECT COUNT(*) as "count!" FROM loans
+                WHERE $1::BOOLEAN IS NULL OR (return_date IS NULL) = $1
+            "#,
+            open
+        )
+        .fetch_one(&self.pool)
+        .await
+        {
+            Ok(total) => total,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to count loans in 'loans' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let loans = match query_as!(
+            LoansJoin,
+            r#"
+                SELECT
+                    loans.id as loan_id, loans.media_id, releases.title as media_title, loans.borrower as loan_borrower,
+                    loans.contact as loan_contact, loans.loan_date as loan_loan_date, loans.due_date as loan_due_date,
+                    loans.return_date as loan_return_date, loans.notes as loan_notes,
+                    loans.created_at as loan_created_at, loans.modified_at as loan_modified_at
+                FROM loans
+                JOIN media ON (media.id = loans.media_id)
+                JOIN releases ON (releases.id = media.release_id)
+                WHERE $1::BOOLEAN IS NULL OR (loans.return_date IS NULL) = $1
+                ORDER BY loans.loan_date DESC, loans.created_at DESC, loans.id
+                LIMIT $2 OFFSET $3
+            "#,
+            open,
+            limit,
+            offset
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(loans) => loans,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch loans from 'loans' table: {}",
+                    err
+                )))
+            }
+        };
+
+        Ok((loans.into_iter().map(Loan::from).collect(), total))
+    }
+
+    /// Returns all open loans which are past their due date, longest overdue first.
+    pub async fn get_overdue_loans(&self) -> Result<Vec<Loan>, StoreError> {
+        let today = OffsetDateTime::now_utc().date();
+
+        let loans = match query_as!(
+            LoansJoin,
+            r#"
+                SELECT
+                    loans.id as loan_id, loans.media_id, releases.title as media_title, loans.borrower as loan_borrower,
+                    loans.contact as loan_contact, loans.loan_date as loan_loan_date, loans.due_date as loan_due_date,
+                    loans.return_date as loan_return_date, loans.notes as loan_notes,
+                    loans.created_at as loan_created_at, loans.modified_at as loan_modified_at
+                FROM loans
+                JOIN media ON (media.id = loans.media_id)
+                JOIN releases ON (releases.id = media.release_id)
+                WHERE loans.return_date IS NULL AND loans.due_date < $1
+                ORDER BY loans.due_date, loans.loan_date, loans.id
+            "#,
+            today
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(loans) => loans,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch loans from 'loans' table: {}",
+                    err
+                )))
+            }
+        };
+
+        Ok(loans.into_iter().map(Loan::from).collect())
+    }
+
+    /// Returns the loan history of the media entry with `media_id`, most recent loan first.
+    pub async fn get_media_loans(&self, media_id: String) -> Result<Vec<Loan>, StoreError> {
+        let exists = match query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM media WHERE id = $1) as "exists!""#,
+            media_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        {
+            Ok(exists) => exists,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to look up media entry: {}",
+                    err
+                )))
+            }
+        };
+
+        if !exists {
+            return Err(StoreError::not_found(format!(
+                "Media entry '{}' does not exist",
+                media_id
+            )));
+        }
+
+        let loans = match query_as!(
+            LoansJoin,
+            r#"
+                SELECT
+                    loans.id as loan_id, loans.media_id, releases.title as media_title, loans.borrower as loan_borrower,
+                    loans.contact as loan_contact, loans.loan_date as loan_loan_date, loans.due_date as loan_due_date,
+                    loans.return_date as loan_return_date, loans.notes as loan_notes,
+                    loans.created_at as loan_created_at, loans.modified_at as loan_modified_at
+                FROM loans
+                JOIN media ON (media.id = loans.media_id)
+                JOIN releases ON (releases.id = media.release_id)
+                WHERE loans.media_id = $1
+                ORDER BY loans.loan_date DESC, loans.created_at DESC, loans.id
+            "#,
+            media_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(loans) => loans,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch loans from 'loans' table: {}",
+                    err
+                )))
+            }
+        };
+
+        Ok(loans.into_iter().map(Loan::from).collect())
+    }
+
+    pub async fn get_loan(&self, id: String) -> Result<Loan, StoreError> {
+        match query_as!(
+            LoansJoin,
+            r#"
+                SELECT
+                    loans.id as loan_id, loans.media_id, releases.title as media_title, loans.borrower as loan_borrower,
+                    loans.contact as loan_contact, loans.loan_date as loan_loan_date, loans.due_date as loan_due_date,
+                    loans.return_date as loan_return_date, loans.notes as loan_notes,
+                    loans.created_at as loan_created_at, loans.modified_at as loan_modified_at
+                FROM loans
+                JOIN media ON (media.id = loans.media_id)
+                JOIN releases ON (releases.id = media.release_id)
+                WHERE loans.id = $1
+            "#,
+            id
+        )
+        .fetch_one(&self.pool)
+        .await
+        {
+            Ok(loan) => Ok(Loan::from(loan)),
+            Err(sqlx::Error::RowNotFound) => {
+                Err(StoreError::not_found(format!("Loan '{}' does not exist", id)))
+            }
+            Err(err) => Err(StoreError::new(format!(
+                "Failed to fetch loan from 'loans' table: {}",
+                err
+            ))),
+        }
+    }
+
+    /// Returns a conflict error if the media entry with `media_id` is currently lent out, ignoring the loan with
+    /// `exclude_id`.
+    async fn check_not_lent(
+        tx: &mut Transaction<'_, Postgres>,
+        media_id: &str,
+        exclude_id: &str,
+    ) -> Result<(), StoreError> {
+        let borrower = match query_scalar!(
+            "SELECT borrower FROM loans WHERE media_id = $1 AND return_date IS NULL AND id != $2",
+            media_id,
+            exclude_id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        {
+            Ok(borrower) => borrower,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch loans from 'loans' table: {}",
+                    err
+                )))
+            }
+        };
+
+        match borrower {
+            Some(borrower) => Err(StoreError::conflict(format!(
+                "Media entry '{}' is already lent to '{}'",
+                media_id, borrower
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Lends the media entry with `media_id` to a borrower. Returns a conflict error if the entry is lent out already.
+    pub async fn create_loan(
+        &self,
+        media_id: String,
+        loan: CreateLoanRequest,
+    ) -> Result<Loan, StoreError> {
+        let borrower = loan.borrower.trim().to_string();
+        if borrower.is_empty() {
+            return Err(StoreError::invalid("Missing borrower"));
+        }
+
+        let loan_date = loan
+            .loan_date
+            .unwrap_or_else(|| OffsetDateTime::now_utc().date());
+        validate_loan_dates(loan_date, loan.due_date, None)?;
+
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        // Locks the media entry, so concurrent loans of it are serialized
+        Self::release_of(&mut tx, &media_id).await?;
+
+        let id = nanoid!();
+        Self::check_not_lent(&mut tx, &media_id, &id).await?;
+
+        let now = Self::now();
+        if let Err(err) = query!(
+            r#"
+                INSERT INTO loans (
+                    id, media_id, borrower, contact, loan_date, due_date, notes, created_at, modified_at
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+            id,
+            media_id,
+            borrower,
+            normalize_text(loan.contact),
+            loan_date,
+            loan.due_date,
+            loan.notes,
+            now,
+            now
+        )
+        .execute(&mut tx)
+        .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to insert into 'loans' table: {}",
+                err
+            )));
+        }
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        self.get_loan(id).await
+    }
+
+    /// Applies a sparse update to the loan with `id`. Reopening a returned loan fails with a conflict error if the
+    /// media entry was lent out again in the meantime.
+    pub async fn update_loan(
+        &self,
+        id: String,
+        loan: UpdateLoanRequest,
+    ) -> Result<Loan, StoreError> {
+        let borrower = loan.borrower.map(|b| b.trim().to_string());
+        if borrower.as_deref() == Some("") {
+            return Err(StoreError::invalid("Borrower must not be empty"));
+        }
+
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        let current = match query!(
+            "SELECT media_id, loan_date, due_date, return_date FROM loans WHERE id = $1 FOR UPDATE",
+            id
+        )
+        .fetch_one(&mut tx)
+        .await
+        {
+            Ok(current) => current,
+            Err(sqlx::Error::RowNotFound) => {
+                return Err(StoreError::not_found(format!(
+                    "Loan '{}' does not exist",
+                    id
+                )))
+            }
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch loan from 'loans' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let loan_date = loan.loan_date.unwrap_or(current.loan_date);
+        let due_date = loan.due_date.unwrap_or(current.due_date);
+        let return_date = loan.return_date.unwrap_or(current.return_date);
+        validate_loan_dates(loan_date, due_date, return_date)?;
+
+        if return_date.is_none() && current.return_date.is_some() {
+            Self::release_of(&mut tx, &current.media_id).await?;
+            Self::check_not_lent(&mut tx, &current.media_id, &id).await?;
+        }
+
+        if let Err(err) = query!(
+            r#"
+                UPDATE loans SET
+                    borrower = COALESCE($2, borrower),
+                    contact = CASE WHEN $3 THEN $4 ELSE contact END,
+                    loan_date = $5,
+                    due_date = $6,
+                    return_date = $7,
+                    notes = COALESCE($8, notes),
+                    modified_at = $9
+                WHERE id = $1
+            "#,
+            id,
+            borrower,
+            loan.contact.is_some(),
+            normalize_text(loan.contact.flatten()),
+            loan_date,
+            due_date,
+            return_date,
+            loan.notes,
+            Self::now()
+        )
+        .execute(&mut tx)
+        .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to update 'loans' table: {}",
+                err
+            )));
+        }
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        self.get_loan(id).await
+    }
+
+    pub async fn delete_loan(&self, id: String) -> Result<(), StoreError> {
+        let result = match query!("DELETE FROM loans WHERE id = $1", id)
+            .execute(&self.pool)
+            .await
+        {
+            Ok(result) => result,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to delete from 'loans' table: {}",
+                    err
+                )))
+            }
+        };
+
+        if result.rows_affected() == 0 {
+            return Err(StoreError::not_found(format!(
+                "Loan '{}' does not exist",
+                id
+            )));
+        }
+
+        Ok(())
+    }
+}
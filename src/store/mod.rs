@@ -1,18 +1,41 @@
 use std::collections::HashMap;
 
-use sqlx::{migrate, postgres::PgPool, query_as, Pool, Postgres};
+use nanoid::nanoid;
+use sqlx::{
+    migrate, postgres::PgPool, query, query_as, query_scalar, types::time::OffsetDateTime, Pool,
+    Postgres, QueryBuilder, Transaction,
+};
 
 use crate::{
     config::StoreOptions,
     store::{
-        error::StoreError,
-        models::{ArtistsJoin, MediaJoin, TracksJoin},
+        error::{StoreError, StoreErrorKind},
+        models::{ArtistsJoin, LabelsJoin, MediaJoin, MediaPageJoin, TracksJoin},
+    },
+    types::{
+        Artist, ArtistRef, BuyCondition, Condition, CreateCopyRequest, CreateMediaRequest,
+        DatePrecision, DiscSize, Label, LabelRef, Link, LinkOwner, Media, MediaCursor,
+        MediaEntriesPage, MediaFilter, MediaFormat, MediaLabel, MediaSort, MediaType, Packaging,
+        ReleaseType, SortOrder, Speed, Track, UpdateMediaRequest,
     },
-    types::{Artist, BuyCondition, Condition, Media, MediaType, Track},
 };
 
-mod error;
+mod artists;
+mod equipment;
+pub mod error;
+mod gradings;
+mod labels;
+mod links;
+mod loans;
 pub mod models;
+mod plays;
+mod pressings;
+mod releases;
+mod search;
+mod shops;
+mod stats;
+mod tracks;
+mod wants;
 
 #[derive(Clone)]
 pub struct Store {
@@ -50,129 +73,758 @@ impl Store {
         };
     }
 
-    fn group_artists_by_media_id(artists: Vec<ArtistsJoin>) -> HashMap<String, Vec<Artist>> {
+    fn group_artists_by_owner_id(
+        artists: Vec<ArtistsJoin>,
+        links: &HashMap<String, Vec<Link>>,
+    ) -> HashMap<String, Vec<Artist>> {
         let mut map: HashMap<String, Vec<Artist>> = HashMap::new();
         for artist in artists {
-            if map.contains_key(&artist.media_id) {
-                if let Some(v) = map.get_mut(&artist.media_id) {
-                    v.push(Artist {
-                        id: artist.artist_id,
-                        name: artist.artist_name,
-                        urls: artist.artist_urls,
-                    })
-                }
-                continue;
-            }
-
-            let v: Vec<Artist> = vec![Artist {
+            map.entry(artist.owner_id).or_default().push(Artist {
+                links: links.get(&artist.artist_id).cloned().unwrap_or_default(),
                 id: artist.artist_id,
                 name: artist.artist_name,
-                urls: artist.artist_urls,
-            }];
-            map.insert(artist.media_id, v);
+            });
+        }
+
+        map
+    }
+
+    fn group_labels_by_owner_id(
+        labels: Vec<LabelsJoin>,
+        links: &HashMap<String, Vec<Link>>,
+    ) -> HashMap<String, Vec<MediaLabel>> {
+        let mut map: HashMap<String, Vec<MediaLabel>> = HashMap::new();
+        for label in labels {
+            map.entry(label.owner_id).or_default().push(MediaLabel {
+                label: Label {
+                    links: links.get(&label.label_id).cloned().unwrap_or_default(),
+                    id: label.label_id,
+                    name: label.label_name,
+                    label_code: label.label_label_code,
+                },
+                catalogue: label.catalogue,
+            });
         }
 
-        return map;
+        map
     }
 
-    fn group_tracks_by_media_id(tracks: Vec<TracksJoin>) -> HashMap<String, Vec<Track>> {
+    fn group_tracks_by_release_id(
+        tracks: Vec<TracksJoin>,
+        links: &mut HashMap<String, Vec<Link>>,
+    ) -> HashMap<String, Vec<Track>> {
         let mut map: HashMap<String, Vec<Track>> = HashMap::new();
         for track in tracks {
-            if map.contains_key(&track.media_id) {
-                if let Some(v) = map.get_mut(&track.media_id) {
-                    v.push(Track {
-                        id: track.track_id,
-                        title: track.track_title,
-                        duration: track.track_duration,
-                        record_side: track.track_record_side,
-                        digital: track.track_digital,
-                        urls: track.track_urls,
-                    });
+            let release_id = track.release_id.clone();
+            let mut track = Track::from(track);
+            track.links = links.remove(&track.id).unwrap_or_default();
+            map.entry(release_id).or_default().push(track);
+        }
+
+        map
+    }
+
+    /// Returns the current UTC time, as stored in the `created_at` and `modified_at` columns.
+    fn now() -> OffsetDateTime {
+        OffsetDateTime::now_utc()
+    }
+
+    /// Returns the ID of the referenced artist. Existing artists are checked for existence, new artists get inserted
+    /// into the `artists` table first.
+    async fn resolve_artist(
+        tx: &mut Transaction<'_, Postgres>,
+        artist: ArtistRef,
+    ) -> Result<String, StoreError> {
+        match artist {
+            ArtistRef::Existing { id } => {
+                let exists = match query_scalar!(
+                    r#"SELECT EXISTS(SELECT 1 FROM artists WHERE id = $1) as "exists!""#,
+                    id
+                )
+                .fetch_one(&mut *tx)
+                .await
+                {
+                    Ok(exists) => exists,
+                    Err(err) => {
+                        return Err(StoreError::new(format!(
+                            "Failed to look up artist: {}",
+                            err
+                        )))
+                    }
+                };
+
+                if !exists {
+                    return Err(StoreError::invalid(format!(
+                        "Artist '{}' does not exist",
+                        id
+                    )));
                 }
-                continue;
+
+                Ok(id)
             }
+            ArtistRef::New { name, links } => {
+                let id = nanoid!();
 
-            let v: Vec<Track> = vec![Track {
-                id: track.track_id,
-                title: track.track_title,
-                duration: track.track_duration,
-                record_side: track.track_record_side,
-                digital: track.track_digital,
-                urls: track.track_urls,
-            }];
-            map.insert(track.media_id, v);
+                if let Err(err) = query!("INSERT INTO artists (id, name) VALUES ($1, $2)", id, name)
+                    .execute(&mut *tx)
+                    .await
+                {
+                    return Err(StoreError::new(format!(
+                        "Failed to insert into 'artists' table: {}",
+                        err
+                    )));
+                }
+
+                Self::set_links(tx, LinkOwner::Artist, &id, links).await?;
+                Ok(id)
+            }
         }
+    }
 
-        return map;
+    /// Returns the ID of the referenced label. Existing labels are checked for existence, new labels get inserted
+    /// into the `labels` table first.
+    async fn resolve_label(
+        tx: &mut Transaction<'_, Postgres>,
+        label: LabelRef,
+    ) -> Result<String, StoreError> {
+        match label {
+            LabelRef::Existing { id } => {
+                let exists = match query_scalar!(
+                    r#"SELECT EXISTS(SELECT 1 FROM labels WHERE id = $1) as "exists!""#,
+                    id
+                )
+                .fetch_one(&mut *tx)
+                .await
+                {
+                    Ok(exists) => exists,
+                    Err(err) => {
+                        return Err(StoreError::new(format!("Failed to look up label: {}", err)))
+                    }
+                };
+
+                if !exists {
+                    return Err(StoreError::invalid(format!(
+                        "Label '{}' does not exist",
+                        id
+                    )));
+                }
+
+                Ok(id)
+            }
+            LabelRef::New {
+                name,
+                label_code,
+                links,
+            } => {
+                let id = nanoid!();
+                let label_code = match Label::normalize_label_code(&label_code) {
+                    Ok(label_code) => label_code,
+                    Err(err) => return Err(StoreError::invalid(err)),
+                };
+
+                if let Err(err) = query!(
+                    "INSERT INTO labels (id, name, label_code) VALUES ($1, $2, $3)",
+                    id,
+                    name,
+                    label_code
+                )
+                .execute(&mut *tx)
+                .await
+                {
+                    return Err(StoreError::new(format!(
+                        "Failed to insert into 'labels' table: {}",
+                        err
+                    )));
+                }
+
+                Self::set_links(tx, LinkOwner::Label, &id, links).await?;
+                Ok(id)
+            }
+        }
     }
 
-    pub async fn create_media(&self, media: Media) -> Result<(), StoreError> {
+    /// Links the artist with `artist_id` to the release with `release_id`. Already existing links are left untouched.
+    async fn link_artist(
+        tx: &mut Transaction<'_, Postgres>,
+        release_id: &str,
+        artist_id: &str,
+    ) -> Result<(), StoreError> {
+        if let Err(err) = query!(
+            r#"
+                INSERT INTO release_artists_rel (release_id, artist_id)
+                SELECT $1::VARCHAR, $2::VARCHAR
+                WHERE NOT EXISTS (SELECT 1 FROM release_artists_rel WHERE release_id = $1 AND artist_id = $2)
+            "#,
+            release_id,
+            artist_id
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to insert into 'release_artists_rel' table: {}",
+                err
+            )));
+        }
+
         Ok(())
     }
 
-    pub async fn get_media_entries(
+    /// Links the label with `label_id` to the release with `release_id` under the `catalogue` number. If the label is
+    /// already linked, only its catalogue number is updated.
+    async fn link_label(
+        tx: &mut Transaction<'_, Postgres>,
+        release_id: &str,
+        label_id: &str,
+        catalogue: &str,
+    ) -> Result<(), StoreError> {
+        if let Err(err) = query!(
+            r#"
+                INSERT INTO release_label_rel (release_id, label_id, catalogue) VALUES ($1, $2, $3)
+                ON CONFLICT (release_id, label_id) DO UPDATE SET catalogue = EXCLUDED.catalogue
+            "#,
+            release_id,
+            label_id,
+            catalogue
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to insert into 'release_label_rel' table: {}",
+                err
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Creates a new media entry including its labels, artists and tracks. All rows are inserted in a single
+    /// transaction, so either the complete entry is stored or nothing at all.
+    pub async fn create_media(&self, media: CreateMediaRequest) -> Result<Media, StoreError> {
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        let media_id = Self::insert_media(&mut tx, media).await?;
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        self.get_media_entry(media_id).await
+    }
+
+    /// Inserts a new release including its labels, artists and tracks together with a first copy of it. Returns the
+    /// ID of the media entry of the copy.
+    async fn insert_media(
+        tx: &mut Transaction<'_, Postgres>,
+        media: CreateMediaRequest,
+    ) -> Result<String, StoreError> {
+        let format = match media.format.normalize(&media.media_type) {
+            Ok(format) => format,
+            Err(err) => return Err(StoreError::invalid(err)),
+        };
+
+        let release_id = nanoid!();
+        let now = Self::now();
+
+        if let Err(err) = query!(
+            r#"
+                INSERT INTO releases (
+                    id, title, media_type, release_date, release_date_precision, created_at, modified_at, disc_size,
+                    speed, release_type, weight, packaging
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            "#,
+            release_id,
+            media.title,
+            media.media_type as _,
+            media.release_date.date,
+            media.release_date.precision as _,
+            now,
+            now,
+            format.size as _,
+            format.speed as _,
+            format.release_type as _,
+            format.weight,
+            format.packaging as _
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to insert into 'releases' table: {}",
+                err
+            )));
+        }
+
+        for label in media.labels {
+            let label_id = Self::resolve_label(tx, label.label).await?;
+            Self::link_label(tx, &release_id, &label_id, &label.catalogue).await?;
+        }
+
+        for artist in media.artists {
+            let artist_id = Self::resolve_artist(tx, artist).await?;
+            Self::link_artist(tx, &release_id, &artist_id).await?;
+        }
+
+        for track in media.tracks {
+            Self::insert_track(tx, &release_id, track).await?;
+        }
+
+        Self::set_links(tx, LinkOwner::Release, &release_id, media.links).await?;
+
+        let copy = CreateCopyRequest {
+            pressing: media.pressing,
+            purchase_date: media.purchase_date,
+            purchase: media.purchase,
+            media_condition: media.media_condition,
+            sleeve_condition: media.sleeve_condition,
+            bought: media.bought,
+            notes: media.notes,
+        };
+        Self::insert_copy(tx, &release_id, copy).await
+    }
+
+    /// Applies a sparse update to the media entry with `id`. Fields which are not set in `media` are left untouched,
+    /// `modified_at` is always bumped. Release fields are applied to the release of the entry, which bumps its
+    /// `modified_at` as well. All changes are applied in a single transaction.
+    pub async fn update_media_entry(
         &self,
-        _offset: usize,
-        _limit: usize,
-    ) -> Result<Vec<Media>, StoreError> {
-        let raw_media_entries = match query_as!(
-            MediaJoin,
+        id: String,
+        media: UpdateMediaRequest,
+    ) -> Result<Media, StoreError> {
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        let mut release_id = Self::release_of(&mut tx, &id).await?;
+
+        // Moving the copy to another release removes its old release once it has no copies left
+        if let Some(new_release_id) = media.release_id {
+            if new_release_id != release_id {
+                match Self::lock_release(&mut tx, &new_release_id).await {
+                    Ok(_) => {}
+                    Err(err) if err.kind() == StoreErrorKind::NotFound => {
+                        return Err(StoreError::invalid(err.to_string()))
+                    }
+                    Err(err) => return Err(err),
+                }
+
+                if let Err(err) = query!(
+                    "UPDATE media SET release_id = $2 WHERE id = $1",
+                    id,
+                    new_release_id
+                )
+                .execute(&mut tx)
+                .await
+                {
+                    return Err(StoreError::new(format!(
+                        "Failed to update 'media' table: {}",
+                        err
+                    )));
+                }
+
+                Self::remove_empty_release(&mut tx, &release_id, false).await?;
+                release_id = new_release_id;
+            }
+        }
+
+        if let Err(err) = query!(
+            r#"
+                UPDATE media SET
+                    purchase_date = COALESCE($2, purchase_date),
+                    media_condition = COALESCE($3, media_condition),
+                    sleeve_condition = COALESCE($4, sleeve_condition),
+                    bought = COALESCE($5, bought),
+                    notes = COALESCE($6, notes),
+                    modified_at = $7
+                WHERE id = $1
+            "#,
+            id,
+            media.purchase_date,
+            media.media_condition as _,
+            media.sleeve_condition as _,
+            media.bought as _,
+            media.notes,
+            Self::now()
+        )
+        .execute(&mut tx)
+        .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to update 'media' table: {}",
+                err
+            )));
+        }
+
+        if let Some(pressing) = media.pressing {
+            Self::set_pressing(&mut tx, &id, pressing).await?;
+        }
+
+        if let Some(purchase) = media.purchase {
+            Self::set_purchase(&mut tx, &id, purchase).await?;
+        }
+
+        if media.media_condition.is_some() || media.sleeve_condition.is_some() {
+            Self::record_current_grading(&mut tx, &id).await?;
+        }
+
+        let media_type_changed = media.media_type.is_some();
+        let release_changed = media.title.is_some()
+            || media_type_changed
+            || media.release_date.is_some()
+            || media.format.is_some()
+            || !media.labels.is_empty()
+            || !media.artists.is_empty()
+            || !media.tracks.is_empty()
+            || media.links.is_some();
+
+        if release_changed {
+            if let Err(err) = query!(
+                r#"
+                    UPDATE releases SET
+                        title = COALESCE($2, title),
+                        media_type = COALESCE($3, media_type),
+                        release_date = COALESCE($4, release_date),
+                        release_date_precision = COALESCE($5, release_date_precision),
+                        modified_at = $6
+                    WHERE id = $1
+                "#,
+                release_id,
+                media.title,
+                media.media_type as _,
+                media.release_date.map(|d| d.date),
+                media.release_date.map(|d| d.precision) as _,
+                Self::now()
+            )
+            .execute(&mut tx)
+            .await
+            {
+                return Err(StoreError::new(format!(
+                    "Failed to update 'releases' table: {}",
+                    err
+                )));
+            }
+        }
+
+        // Format details depend on the media type, so they are validated again whenever one of them changes
+        if media_type_changed || media.format.is_some() {
+            Self::update_release_format(&mut tx, &release_id, media.format).await?;
+        }
+
+        // Labels
+        if let Some(labels) = media.labels.set {
+            if let Err(err) = query!(
+                "DELETE FROM release_label_rel WHERE release_id = $1",
+                release_id
+            )
+            .execute(&mut tx)
+            .await
+            {
+                return Err(StoreError::new(format!(
+                    "Failed to delete from 'release_label_rel' table: {}",
+                    err
+                )));
+            }
+
+            for label in labels {
+                let label_id = Self::resolve_label(&mut tx, label.label).await?;
+                Self::link_label(&mut tx, &release_id, &label_id, &label.catalogue).await?;
+            }
+        }
+
+        for label in media.labels.add {
+            let label_id = Self::resolve_label(&mut tx, label.label).await?;
+            Self::link_label(&mut tx, &release_id, &label_id, &label.catalogue).await?;
+        }
+
+        for label_id in media.labels.remove {
+            let result = match query!(
+                "DELETE FROM release_label_rel WHERE release_id = $1 AND label_id = $2",
+                release_id,
+                label_id
+            )
+            .execute(&mut tx)
+            .await
+            {
+                Ok(result) => result,
+                Err(err) => {
+                    return Err(StoreError::new(format!(
+                        "Failed to delete from 'release_label_rel' table: {}",
+                        err
+                    )))
+                }
+            };
+
+            if result.rows_affected() == 0 {
+                return Err(StoreError::invalid(format!(
+                    "Label '{}' is not linked to media entry '{}'",
+                    label_id, id
+                )));
+            }
+        }
+
+        // Artists
+        if let Some(artists) = media.artists.set {
+            if let Err(err) = query!(
+                "DELETE FROM release_artists_rel WHERE release_id = $1",
+                release_id
+            )
+            .execute(&mut tx)
+            .await
+            {
+                return Err(StoreError::new(format!(
+                    "Failed to delete from 'release_artists_rel' table: {}",
+                    err
+                )));
+            }
+
+            for artist in artists {
+                let artist_id = Self::resolve_artist(&mut tx, artist).await?;
+                Self::link_artist(&mut tx, &release_id, &artist_id).await?;
+            }
+        }
+
+        for artist in media.artists.add {
+            let artist_id = Self::resolve_artist(&mut tx, artist).await?;
+            Self::link_artist(&mut tx, &release_id, &artist_id).await?;
+        }
+
+        for artist_id in media.artists.remove {
+            let result = match query!(
+                "DELETE FROM release_artists_rel WHERE release_id = $1 AND artist_id = $2",
+                release_id,
+                artist_id
+            )
+            .execute(&mut tx)
+            .await
+            {
+                Ok(result) => result,
+                Err(err) => {
+                    return Err(StoreError::new(format!(
+                        "Failed to delete from 'release_artists_rel' table: {}",
+                        err
+                    )))
+                }
+            };
+
+            if result.rows_affected() == 0 {
+                return Err(StoreError::invalid(format!(
+                    "Artist '{}' is not linked to media entry '{}'",
+                    artist_id, id
+                )));
+            }
+        }
+
+        // Tracks
+        if let Some(tracks) = media.tracks.set {
+            Self::remove_release_tracks(&mut tx, &release_id).await?;
+
+            for track in tracks {
+                Self::insert_track(&mut tx, &release_id, track).await?;
+            }
+        }
+
+        for track in media.tracks.add {
+            Self::insert_track(&mut tx, &release_id, track).await?;
+        }
+
+        for track in media.tracks.update {
+            Self::update_track_in_tx(&mut tx, &release_id, &track.id, track.changes).await?;
+        }
+
+        for track_id in media.tracks.remove {
+            Self::remove_track(&mut tx, &release_id, &track_id).await?;
+        }
+
+        if media_type_changed {
+            Self::validate_track_positions(&mut tx, &release_id).await?;
+        }
+
+        // Links
+        if let Some(links) = media.links {
+            Self::set_links(&mut tx, LinkOwner::Release, &release_id, links).await?;
+        }
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        self.get_media_entry(id).await
+    }
+
+    /// Validates the format details of the release with `release_id` against its media type. If `format` is set, it
+    /// replaces the stored format details first.
+    async fn update_release_format(
+        tx: &mut Transaction<'_, Postgres>,
+        release_id: &str,
+        format: Option<MediaFormat>,
+    ) -> Result<(), StoreError> {
+        let current = match query!(
             r#"
                 SELECT
-                    media.id as media_id, media.title as media_title, media.media_type as "media_media_type: MediaType",
-                    media.catalogue as media_catalogue, media.release_date as media_release_date,
-                    media.purchase_date as media_purchase_date, media.media_condition as "media_media_condition: Condition",
-                    media.sleeve_condition as "media_sleeve_condition: Condition", media.bought as "media_bought: BuyCondition",
-                    media.created_at as media_created_at, media.modified_at as media_modified_at, media.notes as media_notes,
-                    -- Label
-                    labels.id as label_id, labels.name as label_name, labels.label_code as label_label_code,
-                    labels.urls as label_urls
-                    FROM media
-                -- Label Joins
-                JOIN media_label_rel ON (media_label_rel.media_id = media.id)
-                JOIN labels ON (media_label_rel.label_id = labels.id)
-            "#
+                    media_type as "media_type: MediaType", disc_size as "disc_size: DiscSize", speed as "speed: Speed",
+                    release_type as "release_type: ReleaseType", weight, packaging as "packaging: Vec<Packaging>"
+                FROM releases WHERE id = $1
+            "#,
+            release_id
         )
-        .fetch_all(&self.pool)
+        .fetch_one(&mut *tx)
         .await
         {
-            Ok(media_entries) => media_entries,
+            Ok(current) => current,
+            Err(sqlx::Error::RowNotFound) => {
+                return Err(StoreError::not_found(format!(
+                    "Release '{}' does not exist",
+                    release_id
+                )))
+            }
             Err(err) => {
                 return Err(StoreError::new(format!(
-                    "Failed to fetch media entries from 'media' table: {}",
+                    "Failed to fetch release from 'releases' table: {}",
                     err
                 )))
             }
         };
 
-        // Collect media ids
+        let format = format.unwrap_or(MediaFormat {
+            size: current.disc_size,
+            speed: current.speed,
+            release_type: current.release_type,
+            weight: current.weight,
+            packaging: current.packaging,
+        });
+
+        let format = match format.normalize(&current.media_type) {
+            Ok(format) => format,
+            Err(err) => return Err(StoreError::invalid(err)),
+        };
+
+        if let Err(err) = query!(
+            r#"
+                UPDATE releases SET disc_size = $2, speed = $3, release_type = $4, weight = $5, packaging = $6
+                WHERE id = $1
+            "#,
+            release_id,
+            format.size as _,
+            format.speed as _,
+            format.release_type as _,
+            format.weight,
+            format.packaging as _
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to update 'releases' table: {}",
+                err
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Converts the raw media rows into [`Media`] entries and fills in the artists, labels and tracks of their releases. The order of
+    /// `raw_media_entries` is preserved.
+    async fn complete_media_entries(
+        &self,
+        raw_media_entries: Vec<MediaJoin>,
+    ) -> Result<Vec<Media>, StoreError> {
+        // Collect media and release ids
         let media_ids = raw_media_entries
             .iter()
             .map(|e| e.media_id.clone())
             .collect::<Vec<String>>();
+        let mut release_ids = raw_media_entries
+            .iter()
+            .map(|e| e.media_release_id.clone())
+            .collect::<Vec<String>>();
+        release_ids.sort();
+        release_ids.dedup();
 
         // Get artists data
         let artists = match query_as!(
             ArtistsJoin,
             r#"
                 SELECT
-                    artists.id as artist_id, artists.name as artist_name, artists.urls as artist_urls,
-                    media_artists_rel.media_id as media_id
+                    artists.id as artist_id, artists.name as artist_name, release_artists_rel.release_id as owner_id
                 FROM artists
-                JOIN media_artists_rel ON (media_artists_rel.media_id = ANY($1))
-                WHERE artists.id = media_artists_rel.artist_id
+                JOIN release_artists_rel ON (release_artists_rel.release_id = ANY($1))
+                WHERE artists.id = release_artists_rel.artist_id
             "#,
-            &media_ids[..]
+            &release_ids[..]
         )
         .fetch_all(&self.pool)
         .await
         {
             Ok(artists) => artists,
-            Err(_) => todo!(),
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch artists from 'artists' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let artist_ids = artists
+            .iter()
+            .map(|a| a.artist_id.clone())
+            .collect::<Vec<String>>();
+        let artist_links = Self::fetch_links(&self.pool, LinkOwner::Artist, &artist_ids).await?;
+        let artists = Self::group_artists_by_owner_id(artists, &artist_links);
+
+        // Get labels data
+        let labels = match query_as!(
+            LabelsJoin,
+            r#"
+                SELECT
+                    labels.id as label_id, labels.name as label_name, labels.label_code as label_label_code,
+                    release_label_rel.catalogue as catalogue, release_label_rel.release_id as owner_id
+                FROM release_label_rel
+                JOIN labels ON (labels.id = release_label_rel.label_id)
+                WHERE release_label_rel.release_id = ANY($1)
+                ORDER BY release_label_rel.id
+            "#,
+            &release_ids[..]
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(labels) => labels,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch labels from 'labels' table: {}",
+                    err
+                )))
+            }
         };
 
-        let artists = Self::group_artists_by_media_id(artists);
-        println!("{}", artists.len());
+        let label_ids = labels
+            .iter()
+            .map(|l| l.label_id.clone())
+            .collect::<Vec<String>>();
+        let label_links = Self::fetch_links(&self.pool, LinkOwner::Label, &label_ids).await?;
+        let labels = Self::group_labels_by_owner_id(labels, &label_links);
 
         // Get tracks data
         let tracks = match query_as!(
@@ -180,134 +832,517 @@ impl Store {
             r#"
                 SELECT
                     tracks.id as track_id, tracks.title as track_title, tracks.duration as track_duration,
-                    tracks.record_side as track_record_side, tracks.digital as track_digital,
-                    tracks.urls as track_urls, tracks.belongs_to as media_id
+                    tracks.disc as track_disc, tracks.record_side as track_record_side,
+                    tracks.position as track_position, tracks.digital as track_digital,
+                    tracks.belongs_to as release_id
                 FROM tracks
                 WHERE tracks.belongs_to = ANY($1)
+                ORDER BY tracks.disc, tracks.record_side, tracks.position
             "#,
-            &media_ids[..]
+            &release_ids[..]
         )
         .fetch_all(&self.pool)
         .await
         {
             Ok(tracks) => tracks,
-            Err(_) => todo!(),
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch tracks from 'tracks' table: {}",
+                    err
+                )))
+            }
         };
 
-        let tracks = Self::group_tracks_by_media_id(tracks);
+        let track_ids = tracks
+            .iter()
+            .map(|t| t.track_id.clone())
+            .collect::<Vec<String>>();
+        let mut track_links = Self::fetch_links(&self.pool, LinkOwner::Track, &track_ids).await?;
+        let tracks = Self::group_tracks_by_release_id(tracks, &mut track_links);
+
+        let mut matrices = Self::fetch_matrices(&self.pool, &media_ids).await?;
+        let mut gradings = Self::fetch_latest_gradings(&self.pool, &media_ids).await?;
+        let mut loans = Self::fetch_open_loans(&self.pool, &media_ids).await?;
+        let mut play_stats = Self::fetch_play_stats(&self.pool, &media_ids).await?;
+        let release_links = Self::fetch_links(&self.pool, LinkOwner::Release, &release_ids).await?;
+
+        let shop_ids = raw_media_entries
+            .iter()
+            .filter_map(|e| e.media_shop_id.clone())
+            .collect::<Vec<String>>();
+        let shops = self.fetch_shops(&shop_ids).await?;
 
         let mut media_entries: Vec<Media> = Vec::new();
         for media_entry in raw_media_entries {
+            let shop_id = media_entry.media_shop_id.clone();
             let mut entry = Media::from(media_entry);
 
-            if let Some(v) = artists.get(&entry.id) {
+            // Copies of the same release share its artists, labels, tracks and links
+            if let Some(v) = artists.get(&entry.release_id) {
                 entry.artists = v.to_vec();
             }
 
-            if let Some(v) = tracks.get(&entry.id) {
-                entry.tracks = v.to_vec();
+            if let Some(v) = labels.get(&entry.release_id) {
+                entry.labels = v.to_vec();
             }
 
+            if let Some(v) = tracks.get(&entry.release_id) {
+                entry.set_tracks(v.to_vec());
+            }
+
+            entry.pressing.matrices = matrices.remove(&entry.id).unwrap_or_default();
+            entry.latest_grading = gradings.remove(&entry.id);
+            entry.loan = loans.remove(&entry.id);
+            if let Some((play_count, last_played)) = play_stats.remove(&entry.id) {
+                entry.play_count = play_count;
+                entry.last_played = Some(last_played);
+            }
+            entry.purchase.shop = shop_id.and_then(|id| shops.get(&id).cloned());
+            entry.links = release_links
+                .get(&entry.release_id)
+                .cloned()
+                .unwrap_or_default();
+
             media_entries.push(entry);
         }
 
         Ok(media_entries)
     }
 
-    pub async fn get_media_entry(&self, id: String) -> Result<Media, StoreError> {
-        // Get base data
-        let raw_media_entry = match query_as!(
-            MediaJoin,
-            r#"
-                SELECT
-                    media.id as media_id, media.title as media_title, media.media_type as "media_media_type: MediaType",
-                    media.catalogue as media_catalogue, media.release_date as media_release_date,
-                    media.purchase_date as media_purchase_date, media.media_condition as "media_media_condition: Condition",
-                    media.sleeve_condition as "media_sleeve_condition: Condition", media.bought as "media_bought: BuyCondition",
-                    media.created_at as media_created_at, media.modified_at as media_modified_at, media.notes as media_notes,
-                    -- Label
-                    labels.id as label_id, labels.name as label_name, labels.label_code as label_label_code,
-                    labels.urls as label_urls
-                FROM media
-                -- Label Joins
-                JOIN media_label_rel ON (media_label_rel.media_id = media.id)
-                JOIN labels ON (media_label_rel.label_id = labels.id)
-                WHERE media.id = $1
-            "#,
-            id
-        )
-        .fetch_one(&self.pool)
-        .await
+    /// Appends the WHERE clause for `filter` to `builder`. Every filter is pushed down as its own AND condition.
+    fn push_media_filter(builder: &mut QueryBuilder<'_, Postgres>, filter: &MediaFilter) {
+        builder.push(" WHERE TRUE");
+
+        if let Some(media_type) = &filter.media_type {
+            builder.push(" AND releases.media_type = ");
+            builder.push_bind(media_type.clone());
+        }
+
+        if let Some(size) = &filter.size {
+            builder.push(" AND releases.disc_size = ");
+            builder.push_bind(*size);
+        }
+
+        if let Some(speed) = &filter.speed {
+            builder.push(" AND releases.speed = ");
+            builder.push_bind(*speed);
+        }
+
+        if let Some(release_type) = &filter.release_type {
+            builder.push(" AND releases.release_type = ");
+            builder.push_bind(*release_type);
+        }
+
+        if let Some(packaging) = &filter.packaging {
+            builder.push(" AND ");
+            builder.push_bind(*packaging);
+            builder.push(" = ANY(releases.packaging)");
+        }
+
+        if let Some(condition) = &filter.media_condition {
+            builder.push(" AND media.media_condition = ");
+            builder.push_bind(*condition);
+        }
+
+        if let Some(condition) = &filter.min_media_condition {
+            builder.push(" AND media.media_condition = ANY(");
+            builder.push_bind(Self::condition_names(condition.at_least()));
+            builder.push(")");
+        }
+
+        if let Some(condition) = &filter.sleeve_condition {
+            builder.push(" AND media.sleeve_condition = ");
+            builder.push_bind(*condition);
+        }
+
+        if let Some(condition) = &filter.min_sleeve_condition {
+            builder.push(" AND media.sleeve_condition = ANY(");
+            builder.push_bind(Self::condition_names(condition.at_least()));
+            builder.push(")");
+        }
+
+        if let Some(shop) = &filter.shop {
+            builder.push(" AND media.shop_id = ");
+            builder.push_bind(shop.clone());
+        }
+
+        if let Some(bought) = &filter.bought {
+            builder.push(" AND media.bought = ");
+            builder.push_bind(bought.clone());
+        }
+
+        if let Some(release_id) = &filter.release {
+            builder.push(" AND media.release_id = ");
+            builder.push_bind(release_id.clone());
+        }
+
+        if let Some(lent) = filter.lent {
+            builder.push(if lent {
+                " AND EXISTS"
+            } else {
+                " AND NOT EXISTS"
+            });
+            builder.push(
+                " (SELECT 1 FROM loans WHERE loans.media_id = media.id AND loans.return_date IS NULL)",
+            );
+        }
+
+        if let Some(label_id) = &filter.label {
+            builder.push(
+                " AND EXISTS (SELECT 1 FROM release_label_rel WHERE release_label_rel.release_id = media.release_id AND release_label_rel.label_id = ",
+            );
+            builder.push_bind(label_id.clone());
+            builder.push(")");
+        }
+
+        if let Some(artist_id) = &filter.artist {
+            builder.push(
+                " AND EXISTS (SELECT 1 FROM release_artists_rel WHERE release_artists_rel.release_id = media.release_id AND release_artists_rel.artist_id = ",
+            );
+            builder.push_bind(artist_id.clone());
+            builder.push(")");
+        }
+
+        // Release dates cover a whole year or month if they are not known precisely. They match if that period
+        // overlaps the requested range.
+        if let Some(date) = filter.release_date_from {
+            builder.push(
+                " AND releases.release_date + CASE releases.release_date_precision WHEN 'Year' THEN INTERVAL '1 year' \
+                 WHEN 'Month' THEN INTERVAL '1 month' ELSE INTERVAL '1 day' END > ",
+            );
+            builder.push_bind(date);
+        }
+
+        if let Some(date) = filter.release_date_to {
+            builder.push(" AND releases.release_date <= ");
+            builder.push_bind(date);
+        }
+
+        if let Some(date) = filter.purchase_date_from {
+            builder.push(" AND media.purchase_date >= ");
+            builder.push_bind(date);
+        }
+
+        if let Some(date) = filter.purchase_date_to {
+            builder.push(" AND media.purchase_date <= ");
+            builder.push_bind(date);
+        }
+    }
+
+    fn condition_names(conditions: Vec<Condition>) -> Vec<String> {
+        conditions.iter().map(|c| c.as_str().to_string()).collect()
+    }
+
+    /// Returns the SQL expression and its type used to sort the media listing by `sort`. Only these whitelisted
+    /// expressions are ever pushed into ORDER BY clauses. Text values are compared case-insensitively, artists are
+    /// sorted by their name without a leading "The".
+    fn media_sort_expression(sort: MediaSort) -> (&'static str, &'static str) {
+        match sort {
+            MediaSort::Title => ("lower(releases.title)", "TEXT"),
+            MediaSort::Artist => (
+                r#"COALESCE((
+                    SELECT MIN(lower(regexp_replace(artists.name, '^the\s+', '', 'i'))) FROM artists
+                    JOIN release_artists_rel ON (release_artists_rel.artist_id = artists.id)
+                    WHERE release_artists_rel.release_id = media.release_id
+                ), '')"#,
+                "TEXT",
+            ),
+            MediaSort::Label => (
+                r#"COALESCE((
+                    SELECT MIN(lower(labels.name)) FROM labels
+                    JOIN release_label_rel ON (release_label_rel.label_id = labels.id)
+                    WHERE release_label_rel.release_id = media.release_id
+                ), '')"#,
+                "TEXT",
+            ),
+            MediaSort::Catalogue => (
+                r#"COALESCE((
+                    SELECT MIN(lower(release_label_rel.catalogue)) FROM release_label_rel
+                    WHERE release_label_rel.release_id = media.release_id
+                ), '')"#,
+                "TEXT",
+            ),
+            MediaSort::ReleaseDate => ("releases.release_date", "DATE"),
+            MediaSort::PurchaseDate => ("media.purchase_date", "DATE"),
+            MediaSort::CreatedAt => ("media.created_at", "TIMESTAMPTZ"),
+            MediaSort::Condition => (
+                r#"CASE media.media_condition
+                    WHEN 'Mint' THEN 11 WHEN 'NearMint' THEN 10 WHEN 'NearMintMinus' THEN 9 WHEN 'VeryGoodPlus' THEN 8
+                    WHEN 'VeryGood' THEN 7 WHEN 'VeryGoodMinus' THEN 6 WHEN 'GoodPlus' THEN 5 WHEN 'Good' THEN 4
+                    WHEN 'GoodMinus' THEN 3 WHEN 'Fair' THEN 2 WHEN 'Poor' THEN 1 ELSE 0
+                END"#,
+                "INT",
+            ),
+            // Entries which were never played sort before all played ones
+            MediaSort::LastPlayed => (
+                r#"COALESCE((
+                    SELECT MAX(plays.played_at) FROM plays WHERE plays.media_id = media.id
+                ), '-infinity')"#,
+                "TIMESTAMPTZ",
+            ),
+            MediaSort::PlayCount => (
+                "(SELECT COUNT(*) FROM plays WHERE plays.media_id = media.id)",
+                "BIGINT",
+            ),
+        }
+    }
+
+    /// Pushes the expression to sort the media listing by `sort` into `builder`. Entries listed for a label are sorted by
+    /// their catalogue number on that label, not by the catalogue number of a co-releasing label.
+    fn push_media_sort_expression(
+        builder: &mut QueryBuilder<'_, Postgres>,
+        sort: MediaSort,
+        filter: &MediaFilter,
+    ) {
+        match (sort, &filter.label) {
+            (MediaSort::Catalogue, Some(label_id)) => {
+                builder.push(
+                    r#"COALESCE((
+                        SELECT MIN(lower(release_label_rel.catalogue)) FROM release_label_rel
+                        WHERE release_label_rel.release_id = media.release_id AND release_label_rel.label_id = "#,
+                );
+                builder.push_bind(label_id.clone());
+                builder.push("), '')");
+            }
+            _ => {
+                builder.push(Self::media_sort_expression(sort).0);
+            }
+        }
+    }
+
+    /// Returns a page of media entries matching `filter`, sorted by `sort` in `order` and then by `id`. If a `cursor`
+    /// is provided, the page starts right after the entry the cursor points to, otherwise `offset` entries are
+    /// skipped. At most `limit` entries are returned.
+    pub async fn get_media_entries(
+        &self,
+        filter: &MediaFilter,
+        sort: MediaSort,
+        order: SortOrder,
+        limit: i64,
+        offset: i64,
+        cursor: Option<MediaCursor>,
+    ) -> Result<MediaEntriesPage, StoreError> {
+        let mut builder = QueryBuilder::new(
+            "SELECT COUNT(*) FROM media JOIN releases ON (releases.id = media.release_id)",
+        );
+        Self::push_media_filter(&mut builder, filter);
+
+        let total = match builder
+            .build_query_as::<(i64,)>()
+            .fetch_one(&self.pool)
+            .await
         {
-            Ok(media_entry) => media_entry,
+            Ok((total,)) => total,
             Err(err) => {
                 return Err(StoreError::new(format!(
-                    "Failed to fetch media entries from 'media' table: {}",
+                    "Failed to count media entries in 'media' table: {}",
                     err
                 )))
             }
         };
 
-        // Get artists data
-        let artists = match query_as!(
-            ArtistsJoin,
+        let (_, sort_type) = Self::media_sort_expression(sort);
+        let (direction, comparison) = match order {
+            SortOrder::Asc => ("ASC", ">"),
+            SortOrder::Desc => ("DESC", "<"),
+        };
+
+        let mut builder = QueryBuilder::new(
             r#"
                 SELECT
-                    artists.id as artist_id, artists.name as artist_name, artists.urls as artist_urls,
-                    media_artists_rel.media_id as media_id
-                FROM artists
-                JOIN media_artists_rel ON (media_artists_rel.media_id = $1)
-                WHERE artists.id = media_artists_rel.artist_id
+                    media.id as media_id, media.release_id as media_release_id, releases.title as media_title,
+                    releases.media_type as media_media_type, releases.disc_size as media_disc_size,
+                    releases.speed as media_speed, releases.release_type as media_release_type,
+                    releases.weight as media_weight, releases.packaging as media_packaging,
+                    media.pressing_plant as media_pressing_plant, media.country as media_country,
+                    media.variant as media_variant, media.edition_number as media_edition_number,
+                    media.edition_size as media_edition_size, releases.release_date as media_release_date,
+                    releases.release_date_precision as media_release_date_precision,
+                    media.purchase_date as media_purchase_date, media.price as media_price,
+                    media.currency as media_currency, media.shop_id as media_shop_id, media.seller as media_seller,
+                    media.order_reference as media_order_reference,
+                    media.media_condition as media_media_condition, media.sleeve_condition as media_sleeve_condition,
+                    media.bought as media_bought, media.created_at as media_created_at,
+                    GREATEST(media.modified_at, releases.modified_at) as media_modified_at, media.notes as media_notes,
+                    -- Sort
             "#,
-            raw_media_entry.media_id
-        )
-        .fetch_all(&self.pool)
-        .await
-        {
-            Ok(artists) => artists,
-            Err(_) => todo!(),
+        );
+        builder.push("(");
+        Self::push_media_sort_expression(&mut builder, sort, filter);
+        builder.push(")::TEXT as sort_value");
+        builder.push(" FROM media JOIN releases ON (releases.id = media.release_id)");
+        Self::push_media_filter(&mut builder, filter);
+
+        // Keyset pagination
+        let offset = match cursor {
+            Some(cursor) => {
+                builder.push(" AND ((");
+                Self::push_media_sort_expression(&mut builder, sort, filter);
+                builder.push(format!("), media.id) {} (CAST(", comparison));
+                builder.push_bind(cursor.value);
+                builder.push(format!(" AS {}), ", sort_type));
+                builder.push_bind(cursor.id);
+                builder.push(")");
+                0
+            }
+            None => offset,
         };
 
-        let artists = Self::group_artists_by_media_id(artists);
+        // Fetch one more entry than requested to know if there is a next page
+        builder.push(" ORDER BY (");
+        Self::push_media_sort_expression(&mut builder, sort, filter);
+        builder.push(format!(") {}, media.id {} LIMIT ", direction, direction));
+        builder.push_bind(limit + 1);
+        builder.push(" OFFSET ");
+        builder.push_bind(offset);
 
-        // Get tracks data
-        let tracks = match query_as!(
-            TracksJoin,
+        let mut rows: Vec<MediaPageJoin> =
+            match builder.build_query_as().fetch_all(&self.pool).await {
+                Ok(rows) => rows,
+                Err(err) => {
+                    return Err(StoreError::new(format!(
+                        "Failed to fetch media entries from 'media' table: {}",
+                        err
+                    )))
+                }
+            };
+
+        let next_cursor = if rows.len() as i64 > limit {
+            rows.truncate(limit as usize);
+            rows.last().map(|row| {
+                MediaCursor {
+                    sort,
+                    order,
+                    value: row.sort_value.clone(),
+                    id: row.media.media_id.clone(),
+                }
+                .to_string()
+            })
+        } else {
+            None
+        };
+
+        let raw_media_entries = rows.into_iter().map(|row| row.media).collect();
+        let media_entries = self.complete_media_entries(raw_media_entries).await?;
+
+        Ok(MediaEntriesPage {
+            entries: media_entries,
+            total,
+            next_cursor,
+        })
+    }
+
+    /// Returns the media entries with the given `ids` in the same order. Unknown IDs are skipped.
+    pub async fn get_media_entries_by_ids(&self, ids: &[String]) -> Result<Vec<Media>, StoreError> {
+        let raw_media_entries = match query_as!(
+            MediaJoin,
             r#"
                 SELECT
-                    tracks.id as track_id, tracks.title as track_title, tracks.duration as track_duration,
-                    tracks.record_side as track_record_side, tracks.digital as track_digital,
-                    tracks.urls as track_urls, tracks.belongs_to as media_id
-                FROM tracks
-                WHERE tracks.belongs_to = $1
+                    media.id as media_id, media.release_id as media_release_id, releases.title as media_title,
+                    releases.media_type as "media_media_type: MediaType", releases.disc_size as "media_disc_size: DiscSize",
+                    releases.speed as "media_speed: Speed", releases.release_type as "media_release_type: ReleaseType",
+                    releases.weight as media_weight, releases.packaging as "media_packaging: Vec<Packaging>",
+                    media.pressing_plant as media_pressing_plant, media.country as media_country,
+                    media.variant as media_variant, media.edition_number as media_edition_number,
+                    media.edition_size as media_edition_size, releases.release_date as media_release_date,
+                    releases.release_date_precision as "media_release_date_precision: DatePrecision",
+                    media.purchase_date as media_purchase_date, media.price as media_price,
+                    media.currency as media_currency, media.shop_id as media_shop_id, media.seller as media_seller,
+                    media.order_reference as media_order_reference,
+                    media.media_condition as "media_media_condition: Condition",
+                    media.sleeve_condition as "media_sleeve_condition: Condition", media.bought as "media_bought: BuyCondition",
+                    media.created_at as media_created_at,
+                    GREATEST(media.modified_at, releases.modified_at) as "media_modified_at!",
+                    media.notes as media_notes
+                FROM media
+                JOIN releases ON (releases.id = media.release_id)
+                WHERE media.id = ANY($1)
+                ORDER BY array_position($1, media.id)
             "#,
-            raw_media_entry.media_id
+            ids
         )
         .fetch_all(&self.pool)
         .await
         {
-            Ok(tracks) => tracks,
-            Err(_) => todo!(),
+            Ok(media_entries) => media_entries,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch media entries from 'media' table: {}",
+                    err
+                )))
+            }
         };
 
-        let tracks = Self::group_tracks_by_media_id(tracks);
+        self.complete_media_entries(raw_media_entries).await
+    }
 
-        let mut media_entry = Media::from(raw_media_entry);
-        if let Some(v) = artists.get(&media_entry.id) {
-            media_entry.artists = v.to_vec();
-        }
-        if let Some(v) = tracks.get(&media_entry.id) {
-            media_entry.tracks = v.to_vec();
+    pub async fn get_media_entry(&self, id: String) -> Result<Media, StoreError> {
+        match self
+            .get_media_entries_by_ids(std::slice::from_ref(&id))
+            .await?
+            .pop()
+        {
+            Some(media_entry) => Ok(media_entry),
+            None => Err(StoreError::not_found(format!(
+                "Media entry '{}' does not exist",
+                id
+            ))),
         }
-
-        Ok(media_entry)
     }
 
-    pub async fn update_media_entry(&self, id: String, new_vinyl: Media) -> Result<(), StoreError> {
-        Ok(())
-    }
+    /// Deletes the media entry with `id`. If it was the last copy of its release, the release is deleted as well
+    /// together with its tracks and artist and label relations. If `remove_orphans` is set, artists and labels of such
+    /// a release which are not linked to anything else are deleted too. Logged plays of the entry are kept for the
+    /// equipment they wore.
+    pub async fn delete_media_entry(
+        &self,
+        id: String,
+        remove_orphans: bool,
+    ) -> Result<(), StoreError> {
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        Self::delete_matrices(&mut tx, std::slice::from_ref(&id)).await?;
+        Self::delete_gradings(&mut tx, &id).await?;
+        Self::delete_loans(&mut tx, &id).await?;
+        Self::detach_plays(&mut tx, &id).await?;
+
+        let release_id =
+            match query_scalar!("DELETE FROM media WHERE id = $1 RETURNING release_id", id)
+                .fetch_one(&mut tx)
+                .await
+            {
+                Ok(release_id) => release_id,
+                Err(sqlx::Error::RowNotFound) => {
+                    return Err(StoreError::not_found(format!(
+                        "Media entry '{}' does not exist",
+                        id
+                    )))
+                }
+                Err(err) => {
+                    return Err(StoreError::new(format!(
+                        "Failed to delete from 'media' table: {}",
+                        err
+                    )))
+                }
+            };
+
+        Self::remove_empty_release(&mut tx, &release_id, remove_orphans).await?;
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
 
-    pub async fn delete_media_entry(&self, id: String) -> Result<(), StoreError> {
         Ok(())
     }
 }
@@ -1,42 +1,177 @@
-use std::collections::HashMap;
+use sqlx::{
+    types::time::{Date, OffsetDateTime},
+    FromRow,
+};
 
-use sqlx::types::time::PrimitiveDateTime;
-
-use crate::types::{Artist, BuyCondition, Condition, MediaType};
+use crate::types::{
+    BuyCondition, Condition, DatePrecision, DiscSize, EquipmentKind, LinkKind, MediaType,
+    Packaging, ReleaseType, Speed, WantPriority,
+};
 
+#[derive(FromRow)]
 pub struct MediaJoin {
     pub media_id: String,
+    pub media_release_id: String,
     pub media_title: String,
     pub media_media_type: MediaType,
-    pub media_catalogue: String,
-    pub media_release_date: PrimitiveDateTime,
-    pub media_purchase_date: PrimitiveDateTime,
+    pub media_disc_size: Option<DiscSize>,
+    pub media_speed: Option<Speed>,
+    pub media_release_type: Option<ReleaseType>,
+    pub media_weight: Option<i32>,
+    pub media_packaging: Vec<Packaging>,
+    pub media_pressing_plant: Option<String>,
+    pub media_country: Option<String>,
+    pub media_variant: Option<String>,
+    pub media_edition_number: Option<i32>,
+    pub media_edition_size: Option<i32>,
+    pub media_release_date: Date,
+    pub media_release_date_precision: DatePrecision,
+    pub media_purchase_date: Date,
+    pub media_price: Option<i64>,
+    pub media_currency: Option<String>,
+    pub media_shop_id: Option<String>,
+    pub media_seller: Option<String>,
+    pub media_order_reference: Option<String>,
     pub media_media_condition: Condition,
     pub media_sleeve_condition: Condition,
     pub media_bought: BuyCondition,
-    pub media_created_at: PrimitiveDateTime,
-    pub media_modified_at: PrimitiveDateTime,
+    pub media_created_at: OffsetDateTime,
+    pub media_modified_at: OffsetDateTime,
     pub media_notes: String,
-    // Label
-    pub label_id: String,
-    pub label_name: String,
-    pub label_label_code: String,
-    pub label_urls: String,
+}
+
+#[derive(FromRow)]
+pub struct MediaPageJoin {
+    #[sqlx(flatten)]
+    pub media: MediaJoin,
+    pub sort_value: String,
 }
 
 pub struct ArtistsJoin {
     pub artist_id: String,
     pub artist_name: String,
-    pub artist_urls: String,
-    pub media_id: String,
+    pub owner_id: String,
+}
+
+pub struct LabelsJoin {
+    pub label_id: String,
+    pub label_name: String,
+    pub label_label_code: String,
+    pub catalogue: String,
+    pub owner_id: String,
 }
 
 pub struct TracksJoin {
     pub track_id: String,
     pub track_title: String,
     pub track_duration: i32,
-    pub track_record_side: String,
+    pub track_disc: i32,
+    pub track_record_side: Option<String>,
+    pub track_position: i32,
     pub track_digital: bool,
-    pub track_urls: String,
+    pub release_id: String,
+}
+
+pub struct SearchRankJoin {
+    pub media_id: String,
+    pub rank: f32,
+}
+
+pub struct SearchHighlightJoin {
+    pub media_id: String,
+    pub field: String,
+    pub highlight: String,
+}
+
+pub struct LinksJoin {
+    pub owner_id: String,
+    pub link_kind: LinkKind,
+    pub link_url: String,
+}
+
+pub struct MatricesJoin {
+    pub media_id: String,
+    pub matrix_side: String,
+    pub matrix_runout: String,
+}
+
+pub struct GradingsJoin {
+    pub grading_id: i32,
     pub media_id: String,
+    pub grading_graded_at: OffsetDateTime,
+    pub grading_grader: Option<String>,
+    pub grading_media_condition: Condition,
+    pub grading_sleeve_condition: Condition,
+    pub grading_notes: String,
+}
+
+pub struct WantsJoin {
+    pub want_id: String,
+    pub want_title: String,
+    pub want_media_type: MediaType,
+    pub want_priority: WantPriority,
+    pub want_max_price: Option<i64>,
+    pub want_max_price_currency: Option<String>,
+    pub want_min_condition: Option<Condition>,
+    pub want_notes: String,
+    pub want_created_at: OffsetDateTime,
+    pub want_modified_at: OffsetDateTime,
+}
+
+pub struct LoansJoin {
+    pub loan_id: String,
+    pub media_id: String,
+    pub media_title: String,
+    pub loan_borrower: String,
+    pub loan_contact: Option<String>,
+    pub loan_loan_date: Date,
+    pub loan_due_date: Option<Date>,
+    pub loan_return_date: Option<Date>,
+    pub loan_notes: String,
+    pub loan_created_at: OffsetDateTime,
+    pub loan_modified_at: OffsetDateTime,
+}
+
+pub struct PlaysJoin {
+    pub play_id: String,
+    pub media_id: String,
+    pub media_title: String,
+    pub play_played_at: OffsetDateTime,
+    pub play_sides: Vec<String>,
+    pub play_duration: i32,
+    pub play_turntable_id: Option<String>,
+    pub play_cartridge_id: Option<String>,
+    pub play_stylus_id: Option<String>,
+    pub play_notes: String,
+    pub play_created_at: OffsetDateTime,
+}
+
+pub struct PlayStatsJoin {
+    pub media_id: String,
+    pub play_count: i64,
+    pub last_played: OffsetDateTime,
+}
+
+pub struct EquipmentJoin {
+    pub equipment_id: String,
+    pub equipment_kind: EquipmentKind,
+    pub equipment_name: String,
+    pub equipment_manufacturer: Option<String>,
+    pub equipment_model: Option<String>,
+    pub equipment_cartridge_id: Option<String>,
+    pub equipment_active: bool,
+    pub equipment_lifetime_hours: Option<i32>,
+    pub equipment_play_count: i64,
+    pub equipment_played_seconds: i64,
+    pub equipment_notes: String,
+    pub equipment_created_at: OffsetDateTime,
+    pub equipment_modified_at: OffsetDateTime,
+}
+
+/// IDs of the equipment in use, the stylus being the one mounted on the active cartridge.
+#[derive(Default)]
+pub struct ActiveEquipment {
+    pub turntable_id: Option<String>,
+    pub cartridge_id: Option<String>,
+    pub stylus_id: Option<String>,
 }
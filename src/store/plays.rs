@@ -0,0 +1,384 @@
+use std::collections::HashMap;
+
+use nanoid::nanoid;
+use sqlx::{
+    query, query_as, query_scalar, types::time::OffsetDateTime, Executor, Postgres, Transaction,
+};
+
+use crate::{
+    store::{
+        error::StoreError,
+        models::{ActiveEquipment, PlayStatsJoin, PlaysJoin},
+        Store,
+    },
+    types::{CreatePlayRequest, MediaType, Play, TrackPosition},
+};
+
+impl Store {
+    /// Returns the play count and the time of the last play of each of the media entries with `media_ids` which was
+    /// played at least once.
+    pub(super) async fn fetch_play_stats<'e, E>(
+        executor: E,
+        media_ids: &[String],
+    ) -> Result<HashMap<String, (i64, OffsetDateTime)>, StoreError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let stats = match query_as!(
+            PlayStatsJoin,
+            r#"
+                SELECT media_id as "media_id!", COUNT(*) as "play_count!", MAX(played_at) as "last_played!"
+                FROM plays
+                WHERE media_id = ANY($1)
+                GROUP BY media_id
+            "#,
+            media_ids
+        )
+        .fetch_all(executor)
+        .await
+        {
+            Ok(stats) => stats,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch plays from 'plays' table: {}",
+                    err
+                )))
+            }
+        };
+
+        Ok(stats
+            .into_iter()
+            .map(|s| (s.media_id, (s.play_count, s.last_played)))
+            .collect())
+    }
+
+    /// Detaches all logged plays from the media entry with `media_id` before it is deleted. The plays are kept, so the
+    /// equipment keeps the hours they accumulated, but they no longer show up in the listening log.
+    pub(super) async fn detach_plays(
+        tx: &mut Transaction<'_, Postgres>,
+        media_id: &str,
+    ) -> Result<(), StoreError> {
+        if let Err(err) = query!(
+            "UPDATE plays SET media_id = NULL WHERE media_id = $1",
+            media_id
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to update 'plays' table: {}",
+                err
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the sides of the release with `release_id` which hold at least one track.
+    async fn release_sides(
+        tx: &mut Transaction<'_, Postgres>,
+        release_id: &str,
+    ) -> Result<Vec<String>, StoreError> {
+        match query_scalar!(
+            r#"
+                SELECT DISTINCT record_side as "record_side!" FROM tracks
+                WHERE belongs_to = $1 AND record_side IS NOT NULL
+            "#,
+            release_id
+        )
+        .fetch_all(&mut *tx)
+        .await
+        {
+            Ok(sides) => Ok(sides),
+            Err(err) => Err(StoreError::new(format!(
+                "Failed to fetch track sides from 'tracks' table: {}",
+                err
+            ))),
+        }
+    }
+
+    /// Returns the runtime in seconds of the records among the `sides` of the release with `release_id`, or among all
+    /// of its sides if no sides are given. Only tracks with a side letter are on a record, so the CDs of a box set are
+    /// left out. Digital-only tracks are not on the record itself, so they are left out as well.
+    async fn groove_duration(
+        tx: &mut Transaction<'_, Postgres>,
+        release_id: &str,
+        sides: &[String],
+    ) -> Result<i32, StoreError> {
+        match query_scalar!(
+            r#"
+                SELECT COALESCE(SUM(duration), 0)::INT as "duration!" FROM tracks
+                WHERE belongs_to = $1 AND record_side IS NOT NULL AND NOT digital
+                AND (cardinality($2::VARCHAR[]) = 0 OR record_side = ANY($2))
+            "#,
+            release_id,
+            sides
+        )
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(duration) => Ok(duration),
+            Err(err) => Err(StoreError::new(format!(
+                "Failed to sum track durations from 'tracks' table: {}",
+                err
+            ))),
+        }
+    }
+
+    /// Returns a page of the listening log, most recent play first, together with the total number of plays. Plays of
+    /// deleted media entries are left out.
+    pub async fn get_plays(&self, limit: i64, offset: i64) -> Result<(Vec<Play>, i64), StoreError> {
+        let total = match query_scalar!(
+            r#"SELECT COUNT(*) as "count!" FROM plays WHERE media_id IS NOT NULL"#
+        )
+        .fetch_one(&self.pool)
+        .await
+        {
+            Ok(total) => total,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to count plays in 'plays' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let plays = match query_as!(
+            PlaysJoin,
+            r#"
+                SELECT
+                    plays.id as play_id, plays.media_id as "media_id!", releases.title as media_title,
+                    plays.played_at as play_played_at, plays.sides as play_sides, plays.duration as play_duration,
+                    plays.turntable_id as play_turntable_id, plays.cartridge_id as play_cartridge_id,
+                    plays.stylus_id as play_stylus_id, plays.notes as play_notes, plays.created_at as play_created_at
+                FROM plays
+                JOIN media ON (media.id = plays.media_id)
+                JOIN releases ON (releases.id = media.release_id)
+                ORDER BY plays.played_at DESC, plays.id
+                LIMIT $1 OFFSET $2
+            "#,
+            limit,
+            offset
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(plays) => plays,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch plays from 'plays' table: {}",
+                    err
+                )))
+            }
+        };
+
+        Ok((plays.into_iter().map(Play::from).collect(), total))
+    }
+
+    /// Returns all logged plays of the media entry with `media_id`, most recent play first.
+    pub async fn get_media_plays(&self, media_id: String) -> Result<Vec<Play>, StoreError> {
+        let exists = match query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM media WHERE id = $1) as "exists!""#,
+            media_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        {
+            Ok(exists) => exists,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to look up media entry: {}",
+                    err
+                )))
+            }
+        };
+
+        if !exists {
+            return Err(StoreError::not_found(format!(
+                "Media entry '{}' does not exist",
+                media_id
+            )));
+        }
+
+        let plays = match query_as!(
+            PlaysJoin,
+            r#"
+                SELECT
+                    plays.id as play_id, plays.media_id as "media_id!", releases.title as media_title,
+                    plays.played_at as play_played_at, plays.sides as play_sides, plays.duration as play_duration,
+                    plays.turntable_id as play_turntable_id, plays.cartridge_id as play_cartridge_id,
+                    plays.stylus_id as play_stylus_id, plays.notes as play_notes, plays.created_at as play_created_at
+                FROM plays
+                JOIN media ON (media.id = plays.media_id)
+                JOIN releases ON (releases.id = media.release_id)
+                WHERE plays.media_id = $1
+                ORDER BY plays.played_at DESC, plays.id
+            "#,
+            media_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(plays) => plays,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch plays from 'plays' table: {}",
+                    err
+                )))
+            }
+        };
+
+        Ok(plays.into_iter().map(Play::from).collect())
+    }
+
+    /// Logs a play of the media entry with `media_id`. The played sides are validated against the media type of the
+    /// entry's release and normalized like track sides, duplicates are dropped. Each played side has to hold a track of
+    /// the release. Plays of records are attributed to the active equipment. Returns the play together with a warning
+    /// if the stylus it was attributed to is wearing out.
+    pub async fn create_play(
+        &self,
+        media_id: String,
+        play: CreatePlayRequest,
+    ) -> Result<(Play, Option<String>), StoreError> {
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        let release_id = Self::release_of(&mut tx, &media_id).await?;
+        let media_type = Self::media_type(&mut tx, &release_id).await?;
+
+        let mut sides: Vec<String> = Vec::new();
+        for side in &play.sides {
+            match TrackPosition::normalize_side(&media_type, None, Some(side)) {
+                Ok((_, Some(side))) if !sides.contains(&side) => sides.push(side),
+                Ok(_) => {}
+                Err(err) => return Err(StoreError::invalid(err)),
+            }
+        }
+
+        if !sides.is_empty() {
+            let release_sides = Self::release_sides(&mut tx, &release_id).await?;
+            if let Some(side) = sides.iter().find(|s| !release_sides.contains(s)) {
+                return Err(StoreError::invalid(format!(
+                    "Invalid side '{}', the release has no tracks on it",
+                    side
+                )));
+            }
+        }
+
+        // Only records wear the stylus. Box sets count as records if the played sides hold any vinyl tracks.
+        let duration = match media_type {
+            MediaType::Vinyl | MediaType::BoxSet => {
+                Self::groove_duration(&mut tx, &release_id, &sides).await?
+            }
+            _ => 0,
+        };
+
+        let active = match media_type {
+            MediaType::Vinyl => Self::active_equipment(&mut tx).await?,
+            MediaType::BoxSet if duration > 0 => Self::active_equipment(&mut tx).await?,
+            _ => ActiveEquipment::default(),
+        };
+
+        let id = nanoid!();
+        let now = Self::now();
+
+        if let Err(err) = query!(
+            r#"
+                INSERT INTO plays (
+                    id, media_id, played_at, sides, duration, turntable_id, cartridge_id, stylus_id, notes, created_at
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#,
+            id,
+            media_id,
+            play.played_at.unwrap_or(now),
+            &sides[..],
+            duration,
+            active.turntable_id,
+            active.cartridge_id,
+            active.stylus_id,
+            play.notes,
+            now
+        )
+        .execute(&mut tx)
+        .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to insert into 'plays' table: {}",
+                err
+            )));
+        }
+
+        let play = match query_as!(
+            PlaysJoin,
+            r#"
+                SELECT
+                    plays.id as play_id, plays.media_id as "media_id!", releases.title as media_title,
+                    plays.played_at as play_played_at, plays.sides as play_sides, plays.duration as play_duration,
+                    plays.turntable_id as play_turntable_id, plays.cartridge_id as play_cartridge_id,
+                    plays.stylus_id as play_stylus_id, plays.notes as play_notes, plays.created_at as play_created_at
+                FROM plays
+                JOIN media ON (media.id = plays.media_id)
+                JOIN releases ON (releases.id = media.release_id)
+                WHERE plays.id = $1
+            "#,
+            id
+        )
+        .fetch_one(&mut tx)
+        .await
+        {
+            Ok(play) => Play::from(play),
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch play from 'plays' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let warning = match &active.stylus_id {
+            Some(stylus_id) => Self::stylus_warning(&mut tx, stylus_id).await?,
+            None => None,
+        };
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        Ok((play, warning))
+    }
+
+    pub async fn delete_play(&self, id: String) -> Result<(), StoreError> {
+        let result = match query!("DELETE FROM plays WHERE id = $1", id)
+            .execute(&self.pool)
+            .await
+        {
+            Ok(result) => result,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to delete from 'plays' table: {}",
+                    err
+                )))
+            }
+        };
+
+        if result.rows_affected() == 0 {
+            return Err(StoreError::not_found(format!(
+                "Play '{}' does not exist",
+                id
+            )));
+        }
+
+        Ok(())
+    }
+}
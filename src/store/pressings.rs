@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+
+use sqlx::{query, query_as, Executor, Postgres, Transaction};
+
+use crate::{
+    store::{error::StoreError, models::MatricesJoin, Store},
+    types::{Matrix, Pressing, RunoutSearchResult},
+};
+
+impl Store {
+    /// Replaces the pressing details of the media entry with `media_id` by `pressing`, including its matrices. The
+    /// pressing details are validated first, see [`Pressing::normalize`].
+    pub(super) async fn set_pressing(
+        tx: &mut Transaction<'_, Postgres>,
+        media_id: &str,
+        pressing: Pressing,
+    ) -> Result<(), StoreError> {
+        let pressing = match pressing.normalize() {
+            Ok(pressing) => pressing,
+            Err(err) => return Err(StoreError::invalid(err)),
+        };
+
+        let result = match query!(
+            r#"
+                UPDATE media SET
+                    pressing_plant = $2, country = $3, variant = $4, edition_number = $5, edition_size = $6
+                WHERE id = $1
+            "#,
+            media_id,
+            pressing.plant,
+            pressing.country,
+            pressing.variant,
+            pressing.edition_number,
+            pressing.edition_size
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            Ok(result) => result,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to update 'media' table: {}",
+                    err
+                )))
+            }
+        };
+
+        if result.rows_affected() == 0 {
+            return Err(StoreError::not_found(format!(
+                "Media entry '{}' does not exist",
+                media_id
+            )));
+        }
+
+        Self::delete_matrices(tx, &[media_id.to_string()]).await?;
+
+        for matrix in pressing.matrices {
+            if let Err(err) = query!(
+                "INSERT INTO matrices (media_id, side, runout) VALUES ($1, $2, $3)",
+                media_id,
+                matrix.side,
+                matrix.runout
+            )
+            .execute(&mut *tx)
+            .await
+            {
+                return Err(StoreError::new(format!(
+                    "Failed to insert into 'matrices' table: {}",
+                    err
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Deletes all matrices of the media entries with `media_ids`.
+    pub(super) async fn delete_matrices(
+        tx: &mut Transaction<'_, Postgres>,
+        media_ids: &[String],
+    ) -> Result<(), StoreError> {
+        if let Err(err) = query!("DELETE FROM matrices WHERE media_id = ANY($1)", media_ids)
+            .execute(&mut *tx)
+            .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to delete from 'matrices' table: {}",
+                err
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the matrices of the media entries with `media_ids`, grouped by media ID and ordered by side.
+    pub(super) async fn fetch_matrices<'e, E>(
+        executor: E,
+        media_ids: &[String],
+    ) -> Result<HashMap<String, Vec<Matrix>>, StoreError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let matrices = match query_as!(
+            MatricesJoin,
+            r#"
+                SELECT media_id, side as matrix_side, runout as matrix_runout FROM matrices
+                WHERE media_id = ANY($1)
+                ORDER BY side
+            "#,
+            media_ids
+        )
+        .fetch_all(executor)
+        .await
+        {
+            Ok(matrices) => matrices,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch matrices from 'matrices' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let mut map: HashMap<String, Vec<Matrix>> = HashMap::new();
+        for matrix in matrices {
+            map.entry(matrix.media_id).or_default().push(Matrix {
+                side: matrix.matrix_side,
+                runout: matrix.matrix_runout,
+            });
+        }
+
+        Ok(map)
+    }
+
+    /// Finds media entries by a partial runout etching. Only letters and digits are compared, so spacing, dashes and
+    /// case do not matter. Entries with the shortest matching runout come first, as they match most closely.
+    pub async fn search_runouts(
+        &self,
+        q: &str,
+        limit: i64,
+    ) -> Result<Vec<RunoutSearchResult>, StoreError> {
+        let matches = match query_as!(
+            MatricesJoin,
+            r#"
+                WITH needle AS (
+                    SELECT upper(regexp_replace($1, '[^[:alnum:]]', '', 'g')) AS key
+                ),
+                matching AS (
+                    SELECT matrices.media_id, matrices.side, matrices.runout, matrices.runout_key
+                    FROM matrices, needle
+                    WHERE needle.key != '' AND strpos(matrices.runout_key, needle.key) > 0
+                ),
+                ranked AS (
+                    SELECT media_id, MIN(length(runout_key)) AS closeness FROM matching
+                    GROUP BY media_id
+                    ORDER BY closeness, media_id
+                    LIMIT $2
+                )
+                SELECT
+                    matching.media_id as "media_id!", matching.side as "matrix_side!",
+                    matching.runout as "matrix_runout!"
+                FROM matching
+                JOIN ranked ON (ranked.media_id = matching.media_id)
+                ORDER BY ranked.closeness, matching.media_id, matching.side
+            "#,
+            q,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(matches) => matches,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to search 'matrices' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let mut media_ids: Vec<String> = Vec::new();
+        let mut matrices: HashMap<String, Vec<Matrix>> = HashMap::new();
+        for matrix in matches {
+            if !matrices.contains_key(&matrix.media_id) {
+                media_ids.push(matrix.media_id.clone());
+            }
+
+            matrices.entry(matrix.media_id).or_default().push(Matrix {
+                side: matrix.matrix_side,
+                runout: matrix.matrix_runout,
+            });
+        }
+
+        let entries = self.get_media_entries_by_ids(&media_ids).await?;
+
+        Ok(entries
+            .into_iter()
+            .map(|entry| RunoutSearchResult {
+                matches: matrices.remove(&entry.id).unwrap_or_default(),
+                entry,
+            })
+            .collect())
+    }
+}
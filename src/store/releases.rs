@@ -0,0 +1,388 @@
+use nanoid::nanoid;
+use sqlx::{query, query_scalar, Postgres, Transaction};
+
+use crate::{
+    store::{error::StoreError, Store},
+    types::{CreateCopyRequest, LinkOwner, Media, Release},
+};
+
+impl Store {
+    /// Returns the ID of the release the media entry with `media_id` is a copy of. The media entry is locked for the
+    /// rest of the transaction. Returns a not found error if the entry does not exist.
+    pub(super) async fn release_of(
+        tx: &mut Transaction<'_, Postgres>,
+        media_id: &str,
+    ) -> Result<String, StoreError> {
+        match query_scalar!(
+            "SELECT release_id FROM media WHERE id = $1 FOR UPDATE",
+            media_id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(release_id) => Ok(release_id),
+            Err(sqlx::Error::RowNotFound) => Err(StoreError::not_found(format!(
+                "Media entry '{}' does not exist",
+                media_id
+            ))),
+            Err(err) => Err(StoreError::new(format!(
+                "Failed to fetch media entry from 'media' table: {}",
+                err
+            ))),
+        }
+    }
+
+    /// Bumps `modified_at` of the release with `release_id`. Returns a not found error if the release does not exist.
+    pub(super) async fn touch_release(
+        tx: &mut Transaction<'_, Postgres>,
+        release_id: &str,
+    ) -> Result<(), StoreError> {
+        let result = match query!(
+            "UPDATE releases SET modified_at = $2 WHERE id = $1",
+            release_id,
+            Self::now()
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            Ok(result) => result,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to update 'releases' table: {}",
+                    err
+                )))
+            }
+        };
+
+        if result.rows_affected() == 0 {
+            return Err(StoreError::not_found(format!(
+                "Release '{}' does not exist",
+                release_id
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Locks the release with `release_id` for the rest of the transaction. Returns a not found error if the release
+    /// does not exist.
+    pub(super) async fn lock_release(
+        tx: &mut Transaction<'_, Postgres>,
+        release_id: &str,
+    ) -> Result<(), StoreError> {
+        match query_scalar!(
+            "SELECT id FROM releases WHERE id = $1 FOR UPDATE",
+            release_id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(_) => Ok(()),
+            Err(sqlx::Error::RowNotFound) => Err(StoreError::not_found(format!(
+                "Release '{}' does not exist",
+                release_id
+            ))),
+            Err(err) => Err(StoreError::new(format!(
+                "Failed to fetch release from 'releases' table: {}",
+                err
+            ))),
+        }
+    }
+
+    /// Inserts a new copy of the release with `release_id` and returns the ID of its media entry.
+    pub(super) async fn insert_copy(
+        tx: &mut Transaction<'_, Postgres>,
+        release_id: &str,
+        copy: CreateCopyRequest,
+    ) -> Result<String, StoreError> {
+        let media_id = nanoid!();
+        let now = Self::now();
+
+        if let Err(err) = query!(
+            r#"
+                INSERT INTO media (
+                    id, release_id, purchase_date, media_condition, sleeve_condition, bought, created_at, modified_at,
+                    notes
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+            media_id,
+            release_id,
+            copy.purchase_date,
+            copy.media_condition as _,
+            copy.sleeve_condition as _,
+            copy.bought as _,
+            now,
+            now,
+            copy.notes
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to insert into 'media' table: {}",
+                err
+            )));
+        }
+
+        Self::set_pressing(tx, &media_id, copy.pressing).await?;
+        Self::set_purchase(tx, &media_id, copy.purchase).await?;
+        Self::record_current_grading(tx, &media_id).await?;
+        Ok(media_id)
+    }
+
+    /// Deletes the release with `release_id` together with its tracks, links and artist and label relations if no
+    /// copy of it is left. If `remove_orphans` is set, artists and labels of the release which are not linked to
+    /// anything else afterwards are deleted as well.
+    pub(super) async fn remove_empty_release(
+        tx: &mut Transaction<'_, Postgres>,
+        release_id: &str,
+        remove_orphans: bool,
+    ) -> Result<(), StoreError> {
+        Self::lock_release(tx, release_id).await?;
+
+        let has_copies = match query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM media WHERE release_id = $1) as "exists!""#,
+            release_id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(has_copies) => has_copies,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to look up copies of release: {}",
+                    err
+                )))
+            }
+        };
+
+        if has_copies {
+            return Ok(());
+        }
+
+        Self::remove_release_tracks(tx, release_id).await?;
+        Self::delete_links(tx, LinkOwner::Release, &[release_id.to_string()]).await?;
+
+        let artist_ids = match query_scalar!(
+            "DELETE FROM release_artists_rel WHERE release_id = $1 RETURNING artist_id",
+            release_id
+        )
+        .fetch_all(&mut *tx)
+        .await
+        {
+            Ok(artist_ids) => artist_ids,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to delete from 'release_artists_rel' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let label_ids = match query_scalar!(
+            "DELETE FROM release_label_rel WHERE release_id = $1 RETURNING label_id",
+            release_id
+        )
+        .fetch_all(&mut *tx)
+        .await
+        {
+            Ok(label_ids) => label_ids,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to delete from 'release_label_rel' table: {}",
+                    err
+                )))
+            }
+        };
+
+        if let Err(err) = query!("DELETE FROM releases WHERE id = $1", release_id)
+            .execute(&mut *tx)
+            .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to delete from 'releases' table: {}",
+                err
+            )));
+        }
+
+        if remove_orphans {
+            let orphaned_artist_ids = match query_scalar!(
+                r#"
+                    DELETE FROM artists
+                    WHERE id = ANY($1)
+                    AND NOT EXISTS (SELECT 1 FROM release_artists_rel WHERE release_artists_rel.artist_id = artists.id)
+                    AND NOT EXISTS (SELECT 1 FROM wants_artists_rel WHERE wants_artists_rel.artist_id = artists.id)
+                    RETURNING id
+                "#,
+                &artist_ids[..]
+            )
+            .fetch_all(&mut *tx)
+            .await
+            {
+                Ok(orphaned_artist_ids) => orphaned_artist_ids,
+                Err(err) => {
+                    return Err(StoreError::new(format!(
+                        "Failed to delete from 'artists' table: {}",
+                        err
+                    )))
+                }
+            };
+
+            Self::delete_links(tx, LinkOwner::Artist, &orphaned_artist_ids).await?;
+
+            let orphaned_label_ids = match query_scalar!(
+                r#"
+                    DELETE FROM labels
+                    WHERE id = ANY($1)
+                    AND NOT EXISTS (SELECT 1 FROM release_label_rel WHERE release_label_rel.label_id = labels.id)
+                    AND NOT EXISTS (SELECT 1 FROM wants_label_rel WHERE wants_label_rel.label_id = labels.id)
+                    RETURNING id
+                "#,
+                &label_ids[..]
+            )
+            .fetch_all(&mut *tx)
+            .await
+            {
+                Ok(orphaned_label_ids) => orphaned_label_ids,
+                Err(err) => {
+                    return Err(StoreError::new(format!(
+                        "Failed to delete from 'labels' table: {}",
+                        err
+                    )))
+                }
+            };
+
+            Self::delete_links(tx, LinkOwner::Label, &orphaned_label_ids).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the releases with the given `ids` in the same order, each with all of its copies. Unknown IDs are
+    /// skipped.
+    async fn get_releases_by_ids(&self, ids: &[String]) -> Result<Vec<Release>, StoreError> {
+        let media_ids = match query_scalar!(
+            r#"
+                SELECT media.id FROM media
+                WHERE media.release_id = ANY($1)
+                ORDER BY array_position($1, media.release_id), media.created_at, media.id
+            "#,
+            ids
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(media_ids) => media_ids,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch copies from 'media' table: {}",
+                    err
+                )))
+            }
+        };
+
+        // Copies of the same release are adjacent, so they can be grouped in one pass
+        let mut groups: Vec<Vec<Media>> = Vec::new();
+        for entry in self.get_media_entries_by_ids(&media_ids).await? {
+            match groups.last_mut() {
+                Some(group) if group[0].release_id == entry.release_id => group.push(entry),
+                _ => groups.push(vec![entry]),
+            }
+        }
+
+        Ok(groups
+            .into_iter()
+            .filter_map(Release::from_copies)
+            .collect())
+    }
+
+    /// Returns a page of releases ordered by title, together with the total number of releases.
+    pub async fn get_releases(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Release>, i64), StoreError> {
+        let total = match query_scalar!(r#"SELECT COUNT(*) as "count!" FROM releases"#)
+            .fetch_one(&self.pool)
+            .await
+        {
+            Ok(total) => total,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to count releases in 'releases' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let ids = match query_scalar!(
+            r#"
+                SELECT id FROM releases
+                ORDER BY lower(title), id
+                LIMIT $1 OFFSET $2
+            "#,
+            limit,
+            offset
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(ids) => ids,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch releases from 'releases' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let releases = self.get_releases_by_ids(&ids).await?;
+        Ok((releases, total))
+    }
+
+    pub async fn get_release(&self, id: String) -> Result<Release, StoreError> {
+        match self
+            .get_releases_by_ids(std::slice::from_ref(&id))
+            .await?
+            .pop()
+        {
+            Some(release) => Ok(release),
+            None => Err(StoreError::not_found(format!(
+                "Release '{}' does not exist",
+                id
+            ))),
+        }
+    }
+
+    /// Adds another copy to the release with `release_id` and returns it as a media entry.
+    pub async fn create_copy(
+        &self,
+        release_id: String,
+        copy: CreateCopyRequest,
+    ) -> Result<Media, StoreError> {
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        // Lock the release, so it cannot be removed along with its last copy in the meantime
+        Self::lock_release(&mut tx, &release_id).await?;
+
+        let media_id = Self::insert_copy(&mut tx, &release_id, copy).await?;
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        self.get_media_entry(media_id).await
+    }
+}
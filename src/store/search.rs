@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use sqlx::query_as;
+
+use crate::{
+    store::{
+        error::StoreError,
+        models::{SearchHighlightJoin, SearchRankJoin},
+        Store,
+    },
+    types::{SearchHighlight, SearchResult},
+};
+
+impl Store {
+    /// Searches media entries by their title and notes, the names of their artists and labels, their catalogue numbers
+    /// and the titles of their tracks. Results are ordered by rank, highest first. The query `q` supports the
+    /// web search syntax of Postgres, e.g. quoted phrases and `-` to exclude terms.
+    pub async fn search_media(&self, q: &str, limit: i64) -> Result<Vec<SearchResult>, StoreError> {
+        let ranks = match query_as!(
+            SearchRankJoin,
+            r#"
+                WITH q AS (SELECT websearch_to_tsquery('simple', $1) AS query)
+                SELECT
+                    media.id as media_id,
+                    (
+                        ts_rank(releases.search, q.query)
+                        + ts_rank(media.search, q.query)
+                        + COALESCE((
+                            SELECT MAX(ts_rank(artists.search, q.query)) FROM artists
+                            JOIN release_artists_rel ON (release_artists_rel.artist_id = artists.id)
+                            WHERE release_artists_rel.release_id = media.release_id AND artists.search @@ q.query
+                        ), 0)
+                        + COALESCE((
+                            SELECT MAX(ts_rank(labels.search, q.query)) FROM labels
+                            JOIN release_label_rel ON (release_label_rel.label_id = labels.id)
+                            WHERE release_label_rel.release_id = media.release_id AND labels.search @@ q.query
+                        ), 0)
+                        + COALESCE((
+                            SELECT MAX(ts_rank(setweight(release_label_rel.search, 'B'), q.query)) FROM release_label_rel
+                            WHERE release_label_rel.release_id = media.release_id AND release_label_rel.search @@ q.query
+                        ), 0)
+                        + COALESCE((
+                            SELECT MAX(ts_rank(tracks.search, q.query)) FROM tracks
+                            WHERE tracks.belongs_to = media.release_id AND tracks.search @@ q.query
+                        ), 0)
+                    ) as "rank!"
+                FROM media
+                JOIN releases ON (releases.id = media.release_id), q
+                WHERE releases.search @@ q.query
+                OR media.search @@ q.query
+                OR EXISTS (
+                    SELECT 1 FROM artists
+                    JOIN release_artists_rel ON (release_artists_rel.artist_id = artists.id)
+                    WHERE release_artists_rel.release_id = media.release_id AND artists.search @@ q.query
+                )
+                OR EXISTS (
+                    SELECT 1 FROM labels
+                    JOIN release_label_rel ON (release_label_rel.label_id = labels.id)
+                    WHERE release_label_rel.release_id = media.release_id AND labels.search @@ q.query
+                )
+                OR EXISTS (
+                    SELECT 1 FROM release_label_rel
+                    WHERE release_label_rel.release_id = media.release_id AND release_label_rel.search @@ q.query
+                )
+                OR EXISTS (
+                    SELECT 1 FROM tracks WHERE tracks.belongs_to = media.release_id AND tracks.search @@ q.query
+                )
+                ORDER BY 2 DESC, media.id
+                LIMIT $2
+            "#,
+            q,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(ranks) => ranks,
+            Err(err) => return Err(StoreError::new(format!("Failed to search media entries: {}", err))),
+        };
+
+        let media_ids = ranks
+            .iter()
+            .map(|r| r.media_id.clone())
+            .collect::<Vec<String>>();
+
+        let highlights = match query_as!(
+            SearchHighlightJoin,
+            r#"
+                WITH q AS (SELECT websearch_to_tsquery('simple', $1) AS query)
+                SELECT media.id as "media_id!", 'title' as "field!", ts_headline('simple', html_escape(releases.title), q.query) as "highlight!"
+                FROM media
+                JOIN releases ON (releases.id = media.release_id), q
+                WHERE media.id = ANY($2) AND releases.search @@ q.query
+                UNION ALL
+                SELECT media.id, 'catalogue', ts_headline('simple', html_escape(release_label_rel.catalogue), q.query)
+                FROM media
+                JOIN release_label_rel ON (release_label_rel.release_id = media.release_id), q
+                WHERE media.id = ANY($2) AND release_label_rel.search @@ q.query
+                UNION ALL
+                SELECT media.id, 'notes', ts_headline('simple', html_escape(media.notes), q.query)
+                FROM media, q
+                WHERE media.id = ANY($2) AND to_tsvector('simple', media.notes) @@ q.query
+                UNION ALL
+                SELECT media.id, 'artist', ts_headline('simple', html_escape(artists.name), q.query)
+                FROM media
+                JOIN release_artists_rel ON (release_artists_rel.release_id = media.release_id)
+                JOIN artists ON (artists.id = release_artists_rel.artist_id), q
+                WHERE media.id = ANY($2) AND artists.search @@ q.query
+                UNION ALL
+                SELECT media.id, 'label', ts_headline('simple', html_escape(labels.name), q.query)
+                FROM media
+                JOIN release_label_rel ON (release_label_rel.release_id = media.release_id)
+                JOIN labels ON (labels.id = release_label_rel.label_id), q
+                WHERE media.id = ANY($2) AND labels.search @@ q.query
+                UNION ALL
+                SELECT media.id, 'track', ts_headline('simple', html_escape(tracks.title), q.query)
+                FROM media
+                JOIN tracks ON (tracks.belongs_to = media.release_id), q
+                WHERE media.id = ANY($2) AND tracks.search @@ q.query
+            "#,
+            q,
+            &media_ids[..]
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(highlights) => highlights,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch search highlights: {}",
+                    err
+                )))
+            }
+        };
+
+        let mut highlights_by_media_id: HashMap<String, Vec<SearchHighlight>> = HashMap::new();
+        for highlight in highlights {
+            highlights_by_media_id
+                .entry(highlight.media_id)
+                .or_default()
+                .push(SearchHighlight {
+                    field: highlight.field,
+                    text: highlight.highlight,
+                });
+        }
+
+        let media_entries = self.get_media_entries_by_ids(&media_ids).await?;
+        let ranks = ranks
+            .into_iter()
+            .map(|r| (r.media_id, r.rank))
+            .collect::<HashMap<String, f32>>();
+
+        let mut results = Vec::new();
+        for entry in media_entries {
+            results.push(SearchResult {
+                rank: ranks.get(&entry.id).copied().unwrap_or_default(),
+                highlights: highlights_by_media_id.remove(&entry.id).unwrap_or_default(),
+                entry,
+            });
+        }
+
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    use super::*;
+
+    #[sqlx::test]
+    async fn escapes_highlighted_text(pool: PgPool) {
+        let store = Store { pool };
+        let media = serde_json::from_value(json!({
+            "title": "<i>Kind</i> of Blue & Green",
+            "media_type": "Vinyl",
+            "artists": [{"name": "Miles Davis"}],
+            "labels": [{"name": "Columbia", "catalogue": "<img src=x onerror=alert(1)> Kind"}],
+            "release_date": "1959-08-17",
+            "purchase_date": "2022-09-01",
+            "media_condition": "NearMint",
+            "sleeve_condition": "VeryGoodPlus",
+            "bought": "Used",
+            "notes": "<script>x</script> kind"
+        }))
+        .unwrap();
+        store.create_media(media).await.unwrap();
+
+        let results = store.search_media("kind", 10).await.unwrap();
+        assert_eq!(results.len(), 1);
+
+        let highlights = results[0]
+            .highlights
+            .iter()
+            .map(|h| (h.field.as_str(), h.text.as_str()))
+            .collect::<HashMap<&str, &str>>();
+        assert_eq!(
+            highlights["title"],
+            "&lt;i&gt;<b>Kind</b>&lt;/i&gt; of Blue &amp; Green"
+        );
+        assert_eq!(
+            highlights["catalogue"],
+            "&lt;img src=x onerror=alert(1)&gt; <b>Kind</b>"
+        );
+        assert_eq!(
+            highlights["notes"],
+            "&lt;script&gt;x&lt;/script&gt; <b>kind</b>"
+        );
+    }
+}
@@ -0,0 +1,435 @@
+use std::collections::HashMap;
+
+use nanoid::nanoid;
+use sqlx::{query, query_scalar, Postgres, Transaction};
+
+use crate::{
+    store::{error::StoreError, Store},
+    types::{
+        Amount, CreateShopRequest, LinkOwner, PurchaseRequest, Shop, ShopRef, ShopSummary,
+        Spending, UpdateShopRequest,
+    },
+};
+
+/// Trims `text` and drops it if it is empty.
+pub(super) fn normalize_text(text: Option<String>) -> Option<String> {
+    text.map(|t| t.trim().to_string()).filter(|t| !t.is_empty())
+}
+
+impl Store {
+    /// Returns the shops with `shop_ids` including their links, keyed by shop ID.
+    pub(super) async fn fetch_shops(
+        &self,
+        shop_ids: &[String],
+    ) -> Result<HashMap<String, Shop>, StoreError> {
+        let rows = match query!(
+            "SELECT id, name, city, online FROM shops WHERE id = ANY($1)",
+            shop_ids
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(rows) => rows,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch shops from 'shops' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let mut links = Self::fetch_links(&self.pool, LinkOwner::Shop, shop_ids).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| {
+                let shop = Shop {
+                    links: links.remove(&r.id).unwrap_or_default(),
+                    id: r.id.clone(),
+                    name: r.name,
+                    city: r.city,
+                    online: r.online,
+                };
+                (r.id, shop)
+            })
+            .collect())
+    }
+
+    /// Adds the number of purchases and the spending per currency to each of the `shops`.
+    async fn summarize_shops(&self, shops: Vec<Shop>) -> Result<Vec<ShopSummary>, StoreError> {
+        let shop_ids = shops.iter().map(|s| s.id.clone()).collect::<Vec<String>>();
+
+        let rows = match query!(
+            r#"
+                SELECT
+                    shop_id as "shop_id!", currency, COALESCE(SUM(price), 0)::BIGINT as "total!",
+                    COUNT(*) as "purchases!", COUNT(price) as "priced!"
+                FROM media
+                WHERE shop_id = ANY($1)
+                GROUP BY shop_id, currency
+                ORDER BY shop_id, currency
+            "#,
+            &shop_ids[..]
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(rows) => rows,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to aggregate spending from 'media' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let mut purchases: HashMap<String, i64> = HashMap::new();
+        let mut spending: HashMap<String, Vec<Spending>> = HashMap::new();
+        for row in rows {
+            *purchases.entry(row.shop_id.clone()).or_default() += row.purchases;
+
+            // Purchases without a price have no currency either
+            if let Some(currency) = row.currency {
+                spending.entry(row.shop_id).or_default().push(Spending {
+                    total: Amount::new(row.total, &currency),
+                    currency,
+                    purchases: row.priced,
+                });
+            }
+        }
+
+        Ok(shops
+            .into_iter()
+            .map(|shop| ShopSummary {
+                purchases: purchases.remove(&shop.id).unwrap_or_default(),
+                spending: spending.remove(&shop.id).unwrap_or_default(),
+                shop,
+            })
+            .collect())
+    }
+
+    /// Returns a page of shops ordered by name together with the total number of shops.
+    pub async fn get_shops(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<ShopSummary>, i64), StoreError> {
+        let total = match query_scalar!(r#"SELECT COUNT(*) as "count!" FROM shops"#)
+            .fetch_one(&self.pool)
+            .await
+        {
+            Ok(total) => total,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to count shops in 'shops' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let shop_ids = match query_scalar!(
+            "SELECT id FROM shops ORDER BY lower(name), id LIMIT $1 OFFSET $2",
+            limit,
+            offset
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(shop_ids) => shop_ids,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch shops from 'shops' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let mut shops = self.fetch_shops(&shop_ids).await?;
+        let shops = shop_ids.iter().filter_map(|id| shops.remove(id)).collect();
+
+        Ok((self.summarize_shops(shops).await?, total))
+    }
+
+    pub async fn get_shop(&self, id: String) -> Result<ShopSummary, StoreError> {
+        let shop = match self
+            .fetch_shops(std::slice::from_ref(&id))
+            .await?
+            .remove(&id)
+        {
+            Some(shop) => shop,
+            None => {
+                return Err(StoreError::not_found(format!(
+                    "Shop '{}' does not exist",
+                    id
+                )))
+            }
+        };
+
+        match self.summarize_shops(vec![shop]).await?.pop() {
+            Some(shop) => Ok(shop),
+            None => Err(StoreError::new("Failed to summarize shop")),
+        }
+    }
+
+    pub async fn create_shop(&self, shop: CreateShopRequest) -> Result<ShopSummary, StoreError> {
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        let id = Self::resolve_shop(
+            &mut tx,
+            ShopRef::New {
+                name: shop.name,
+                city: shop.city,
+                online: shop.online,
+                links: shop.links,
+            },
+        )
+        .await?;
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        self.get_shop(id).await
+    }
+
+    /// Applies a sparse update to the shop with `id`. Fields which are not set in `shop` are left untouched.
+    pub async fn update_shop(
+        &self,
+        id: String,
+        shop: UpdateShopRequest,
+    ) -> Result<ShopSummary, StoreError> {
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        let result = match query!(
+            r#"
+                UPDATE shops SET
+                    name = COALESCE($2, name),
+                    city = CASE WHEN $3 THEN $4 ELSE city END,
+                    online = COALESCE($5, online)
+                WHERE id = $1
+            "#,
+            id,
+            shop.name,
+            shop.city.is_some(),
+            normalize_text(shop.city),
+            shop.online
+        )
+        .execute(&mut tx)
+        .await
+        {
+            Ok(result) => result,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to update 'shops' table: {}",
+                    err
+                )))
+            }
+        };
+
+        if result.rows_affected() == 0 {
+            return Err(StoreError::not_found(format!(
+                "Shop '{}' does not exist",
+                id
+            )));
+        }
+
+        if let Some(links) = shop.links {
+            Self::set_links(&mut tx, LinkOwner::Shop, &id, links).await?;
+        }
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        self.get_shop(id).await
+    }
+
+    /// Deletes the shop with `id` and its links. Shops which media entries were bought at can not be deleted.
+    pub async fn delete_shop(&self, id: String) -> Result<(), StoreError> {
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        let in_use = match query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM media WHERE shop_id = $1) as "exists!""#,
+            id
+        )
+        .fetch_one(&mut tx)
+        .await
+        {
+            Ok(in_use) => in_use,
+            Err(err) => return Err(StoreError::new(format!("Failed to look up shop: {}", err))),
+        };
+
+        if in_use {
+            return Err(StoreError::conflict(format!(
+                "Media entries were bought at shop '{}'",
+                id
+            )));
+        }
+
+        let result = match query!("DELETE FROM shops WHERE id = $1", id)
+            .execute(&mut tx)
+            .await
+        {
+            Ok(result) => result,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to delete from 'shops' table: {}",
+                    err
+                )))
+            }
+        };
+
+        if result.rows_affected() == 0 {
+            return Err(StoreError::not_found(format!(
+                "Shop '{}' does not exist",
+                id
+            )));
+        }
+
+        Self::delete_links(&mut tx, LinkOwner::Shop, &[id]).await?;
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Resolves a shop reference to a shop ID. Existing shops have to exist, new shops are created.
+    pub(super) async fn resolve_shop(
+        tx: &mut Transaction<'_, Postgres>,
+        shop: ShopRef,
+    ) -> Result<String, StoreError> {
+        match shop {
+            ShopRef::Existing { id } => {
+                let exists = match query_scalar!(
+                    r#"SELECT EXISTS(SELECT 1 FROM shops WHERE id = $1) as "exists!""#,
+                    id
+                )
+                .fetch_one(&mut *tx)
+                .await
+                {
+                    Ok(exists) => exists,
+                    Err(err) => {
+                        return Err(StoreError::new(format!("Failed to look up shop: {}", err)))
+                    }
+                };
+
+                if !exists {
+                    return Err(StoreError::invalid(format!("Shop '{}' does not exist", id)));
+                }
+
+                Ok(id)
+            }
+            ShopRef::New {
+                name,
+                city,
+                online,
+                links,
+            } => {
+                if name.trim().is_empty() {
+                    return Err(StoreError::invalid("Missing shop name"));
+                }
+
+                let id = nanoid!();
+
+                if let Err(err) = query!(
+                    "INSERT INTO shops (id, name, city, online) VALUES ($1, $2, $3, $4)",
+                    id,
+                    name.trim(),
+                    normalize_text(city),
+                    online
+                )
+                .execute(&mut *tx)
+                .await
+                {
+                    return Err(StoreError::new(format!(
+                        "Failed to insert into 'shops' table: {}",
+                        err
+                    )));
+                }
+
+                Self::set_links(tx, LinkOwner::Shop, &id, links).await?;
+                Ok(id)
+            }
+        }
+    }
+
+    /// Replaces the purchase details of the media entry with `media_id`. A new shop in `purchase` is created first.
+    pub(super) async fn set_purchase(
+        tx: &mut Transaction<'_, Postgres>,
+        media_id: &str,
+        purchase: PurchaseRequest,
+    ) -> Result<(), StoreError> {
+        let price = match purchase.price.map(|p| p.normalize()).transpose() {
+            Ok(price) => price,
+            Err(err) => return Err(StoreError::invalid(err)),
+        };
+
+        let shop_id = match purchase.shop {
+            Some(shop) => Some(Self::resolve_shop(tx, shop).await?),
+            None => None,
+        };
+
+        let (amount, currency) = match price {
+            Some(price) => (Some(price.amount.minor), Some(price.currency)),
+            None => (None, None),
+        };
+
+        if let Err(err) = query!(
+            r#"
+                UPDATE media SET price = $2, currency = $3, shop_id = $4, seller = $5, order_reference = $6
+                WHERE id = $1
+            "#,
+            media_id,
+            amount,
+            currency,
+            shop_id,
+            normalize_text(purchase.seller),
+            normalize_text(purchase.order_reference)
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to update 'media' table: {}",
+                err
+            )));
+        }
+
+        Ok(())
+    }
+}
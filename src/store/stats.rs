@@ -0,0 +1,257 @@
+use sqlx::{query, query_as, query_scalar, Postgres, Transaction};
+
+use crate::{
+    store::{error::StoreError, Store},
+    types::{
+        Amount, Condition, ConditionCount, DecadeCount, Grade, MediaTypeCount, MonthCount,
+        NamedCount, Stats, YearlySpending,
+    },
+};
+
+impl Store {
+    /// Computes the collection statistics. Labels and artists are limited to the `top` ones with the most media
+    /// entries. All numbers are read in one transaction, so they are consistent with each other.
+    pub async fn get_stats(&self, top: i64) -> Result<Stats, StoreError> {
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        if let Err(err) = query!("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ READ ONLY")
+            .execute(&mut tx)
+            .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to set transaction isolation level: {}",
+                err
+            )));
+        }
+
+        let total = match query_scalar!(r#"SELECT COUNT(*) as "count!" FROM media"#)
+            .fetch_one(&mut tx)
+            .await
+        {
+            Ok(total) => total,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to count media entries in 'media' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let media_types = match query_as!(
+            MediaTypeCount,
+            r#"
+                SELECT releases.media_type as "media_type: _", COUNT(*) as "count!"
+                FROM media
+                JOIN releases ON (releases.id = media.release_id)
+                GROUP BY releases.media_type
+                ORDER BY COUNT(*) DESC, releases.media_type
+            "#
+        )
+        .fetch_all(&mut tx)
+        .await
+        {
+            Ok(media_types) => media_types,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to count media types in 'media' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let media_conditions = Self::count_conditions(&mut tx, false).await?;
+        let sleeve_conditions = Self::count_conditions(&mut tx, true).await?;
+
+        let decades = match query_as!(
+            DecadeCount,
+            r#"
+                SELECT (EXTRACT(YEAR FROM releases.release_date)::INT / 10 * 10) as "decade!", COUNT(*) as "count!"
+                FROM media
+                JOIN releases ON (releases.id = media.release_id)
+                GROUP BY 1
+                ORDER BY 1
+            "#
+        )
+        .fetch_all(&mut tx)
+        .await
+        {
+            Ok(decades) => decades,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to count release decades in 'media' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let labels = match query_as!(
+            NamedCount,
+            r#"
+                SELECT labels.id, labels.name, COUNT(DISTINCT media.id) as "count!"
+                FROM labels
+                JOIN release_label_rel ON release_label_rel.label_id = labels.id
+                JOIN media ON media.release_id = release_label_rel.release_id
+                GROUP BY labels.id
+                ORDER BY 3 DESC, lower(labels.name), labels.id
+                LIMIT $1
+            "#,
+            top
+        )
+        .fetch_all(&mut tx)
+        .await
+        {
+            Ok(labels) => labels,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to count media entries per label: {}",
+                    err
+                )))
+            }
+        };
+
+        let artists = match query_as!(
+            NamedCount,
+            r#"
+                SELECT artists.id, artists.name, COUNT(DISTINCT media.id) as "count!"
+                FROM artists
+                JOIN release_artists_rel ON release_artists_rel.artist_id = artists.id
+                JOIN media ON media.release_id = release_artists_rel.release_id
+                GROUP BY artists.id
+                ORDER BY 3 DESC, lower(artists.name), artists.id
+                LIMIT $1
+            "#,
+            top
+        )
+        .fetch_all(&mut tx)
+        .await
+        {
+            Ok(artists) => artists,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to count media entries per artist: {}",
+                    err
+                )))
+            }
+        };
+
+        let spending = match query!(
+            r#"
+                SELECT
+                    EXTRACT(YEAR FROM purchase_date)::INT as "year!", currency as "currency!",
+                    SUM(price)::BIGINT as "total!", COUNT(*) as "purchases!"
+                FROM media
+                WHERE price IS NOT NULL AND currency IS NOT NULL
+                GROUP BY 1, 2
+                ORDER BY 1, 2
+            "#
+        )
+        .fetch_all(&mut tx)
+        .await
+        {
+            Ok(rows) => rows
+                .into_iter()
+                .map(|r| YearlySpending {
+                    year: r.year,
+                    total: Amount::new(r.total, &r.currency),
+                    currency: r.currency,
+                    purchases: r.purchases,
+                })
+                .collect(),
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to aggregate spending from 'media' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let added = match query_as!(
+            MonthCount,
+            r#"
+                SELECT to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM') as "month!", COUNT(*) as "count!"
+                FROM media
+                GROUP BY 1
+                ORDER BY 1
+            "#
+        )
+        .fetch_all(&mut tx)
+        .await
+        {
+            Ok(added) => added,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to count added media entries in 'media' table: {}",
+                    err
+                )))
+            }
+        };
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        Ok(Stats {
+            total,
+            media_types,
+            media_conditions,
+            sleeve_conditions,
+            decades,
+            labels,
+            artists,
+            spending,
+            added,
+        })
+    }
+
+    /// Counts media entries per media condition, or per sleeve condition if `sleeve` is set. The counts are ordered
+    /// from the best to the worst condition.
+    async fn count_conditions(
+        tx: &mut Transaction<'_, Postgres>,
+        sleeve: bool,
+    ) -> Result<Vec<ConditionCount>, StoreError> {
+        let rows = match query!(
+            r#"
+                SELECT
+                    CASE WHEN $1 THEN sleeve_condition ELSE media_condition END as "condition!: Condition",
+                    COUNT(*) as "count!"
+                FROM media
+                GROUP BY 1
+            "#,
+            sleeve
+        )
+        .fetch_all(&mut *tx)
+        .await
+        {
+            Ok(rows) => rows,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to count conditions in 'media' table: {}",
+                    err
+                )))
+            }
+        };
+
+        Ok(Condition::ALL
+            .iter()
+            .filter_map(|condition| {
+                rows.iter()
+                    .find(|r| r.condition == *condition)
+                    .map(|r| ConditionCount {
+                        condition: Grade::from(r.condition),
+                        count: r.count,
+                    })
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,610 @@
+use nanoid::nanoid;
+use sqlx::{query, query_as, query_scalar, Postgres, Transaction};
+
+use crate::{
+    store::{error::StoreError, models::TracksJoin, Store},
+    types::{CreateTrackRequest, LinkOwner, MediaType, Track, TrackPosition, UpdateTrackRequest},
+};
+
+impl Store {
+    /// Bumps `modified_at` of the media entry with `media_id`. Returns a not found error if the entry does not exist.
+    pub(super) async fn touch_media(
+        tx: &mut Transaction<'_, Postgres>,
+        media_id: &str,
+    ) -> Result<(), StoreError> {
+        let result = match query!(
+            "UPDATE media SET modified_at = $2 WHERE id = $1",
+            media_id,
+            Self::now()
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            Ok(result) => result,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to update 'media' table: {}",
+                    err
+                )))
+            }
+        };
+
+        if result.rows_affected() == 0 {
+            return Err(StoreError::not_found(format!(
+                "Media entry '{}' does not exist",
+                media_id
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the media type of the release with `release_id`. Returns a not found error if the release does not
+    /// exist.
+    pub(super) async fn media_type(
+        tx: &mut Transaction<'_, Postgres>,
+        release_id: &str,
+    ) -> Result<MediaType, StoreError> {
+        match query_scalar!(
+            r#"SELECT media_type as "media_type: MediaType" FROM releases WHERE id = $1"#,
+            release_id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(media_type) => Ok(media_type),
+            Err(sqlx::Error::RowNotFound) => Err(StoreError::not_found(format!(
+                "Release '{}' does not exist",
+                release_id
+            ))),
+            Err(err) => Err(StoreError::new(format!(
+                "Failed to fetch release from 'releases' table: {}",
+                err
+            ))),
+        }
+    }
+
+    /// Checks that the positions of all tracks of the release with `release_id` are valid for its media type, e.g.
+    /// after the media type was changed.
+    pub(super) async fn validate_track_positions(
+        tx: &mut Transaction<'_, Postgres>,
+        release_id: &str,
+    ) -> Result<(), StoreError> {
+        let media_type = Self::media_type(tx, release_id).await?;
+
+        let tracks = match query!(
+            "SELECT id, disc, record_side FROM tracks WHERE belongs_to = $1",
+            release_id
+        )
+        .fetch_all(&mut *tx)
+        .await
+        {
+            Ok(tracks) => tracks,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch tracks from 'tracks' table: {}",
+                    err
+                )))
+            }
+        };
+
+        for track in tracks {
+            let side = track.record_side.as_deref();
+            match TrackPosition::normalize_side(&media_type, Some(track.disc), side) {
+                Ok((disc, side)) if disc == track.disc && side == track.record_side => {}
+                Ok(_) => {
+                    return Err(StoreError::invalid(format!(
+                        "Track '{}' has an invalid position for the media type",
+                        track.id
+                    )))
+                }
+                Err(err) => {
+                    return Err(StoreError::invalid(format!(
+                        "Track '{}' has an invalid position: {}",
+                        track.id, err
+                    )))
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of tracks on the `side` of `disc` of the release, ignoring the track with `exclude_id`.
+    async fn count_side_tracks(
+        tx: &mut Transaction<'_, Postgres>,
+        release_id: &str,
+        disc: i32,
+        side: Option<&str>,
+        exclude_id: &str,
+    ) -> Result<i32, StoreError> {
+        match query_scalar!(
+            r#"
+                SELECT COUNT(*)::INT as "count!" FROM tracks
+                WHERE belongs_to = $1 AND disc = $2 AND record_side IS NOT DISTINCT FROM $3 AND id != $4
+            "#,
+            release_id,
+            disc,
+            side,
+            exclude_id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(count) => Ok(count),
+            Err(err) => Err(StoreError::new(format!(
+                "Failed to count tracks in 'tracks' table: {}",
+                err
+            ))),
+        }
+    }
+
+    /// Moves the tracks on the `side` of `disc` of the release at or after index `from` by `delta`, ignoring the
+    /// track with `exclude_id`. Used to open or close a gap when a track is inserted, moved or removed.
+    async fn shift_side_tracks(
+        tx: &mut Transaction<'_, Postgres>,
+        release_id: &str,
+        disc: i32,
+        side: Option<&str>,
+        from: i32,
+        delta: i32,
+        exclude_id: &str,
+    ) -> Result<(), StoreError> {
+        if let Err(err) = query!(
+            r#"
+                UPDATE tracks SET position = position + $5
+                WHERE belongs_to = $1 AND disc = $2 AND record_side IS NOT DISTINCT FROM $3 AND position >= $4
+                AND id != $6
+            "#,
+            release_id,
+            disc,
+            side,
+            from,
+            delta,
+            exclude_id
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to update 'tracks' table: {}",
+                err
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the index a track is placed at on a side which currently holds `count` other tracks. Requested indices
+    /// are clamped to the end of the side, no index means appending.
+    fn slot_index(requested: Option<i32>, count: i32) -> Result<i32, StoreError> {
+        match requested {
+            Some(index) if index < 1 => Err(StoreError::invalid(format!(
+                "Invalid track index {}, indices start at 1",
+                index
+            ))),
+            Some(index) => Ok(index.min(count + 1)),
+            None => Ok(count + 1),
+        }
+    }
+
+    pub(super) async fn insert_track(
+        tx: &mut Transaction<'_, Postgres>,
+        release_id: &str,
+        track: CreateTrackRequest,
+    ) -> Result<Track, StoreError> {
+        let id = nanoid!();
+
+        let media_type = Self::media_type(tx, release_id).await?;
+        let (disc, side) = match TrackPosition::normalize_side(
+            &media_type,
+            track.position.disc,
+            track.position.side.as_deref(),
+        ) {
+            Ok(position) => position,
+            Err(err) => return Err(StoreError::invalid(err)),
+        };
+
+        let count = Self::count_side_tracks(tx, release_id, disc, side.as_deref(), &id).await?;
+        let index = Self::slot_index(track.position.index, count)?;
+        Self::shift_side_tracks(tx, release_id, disc, side.as_deref(), index, 1, &id).await?;
+
+        match query_as!(
+            TracksJoin,
+            r#"
+                INSERT INTO tracks (id, title, duration, disc, record_side, position, digital, belongs_to)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                RETURNING
+                    id as track_id, title as track_title, duration as track_duration, disc as track_disc,
+                    record_side as track_record_side, position as track_position, digital as track_digital,
+                    belongs_to as release_id
+            "#,
+            id,
+            track.title,
+            track.duration.0,
+            disc,
+            side,
+            index,
+            track.digital,
+            release_id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(row) => {
+                let mut row = Track::from(row);
+                row.links = Self::set_links(tx, LinkOwner::Track, &id, track.links).await?;
+                Ok(row)
+            }
+            Err(err) => Err(StoreError::new(format!(
+                "Failed to insert into 'tracks' table: {}",
+                err
+            ))),
+        }
+    }
+
+    pub(super) async fn update_track_in_tx(
+        tx: &mut Transaction<'_, Postgres>,
+        release_id: &str,
+        track_id: &str,
+        track: UpdateTrackRequest,
+    ) -> Result<Track, StoreError> {
+        let current = match query!(
+            r#"
+                SELECT disc, record_side, position FROM tracks
+                WHERE id = $1 AND belongs_to = $2
+                FOR UPDATE
+            "#,
+            track_id,
+            release_id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(current) => current,
+            Err(sqlx::Error::RowNotFound) => {
+                return Err(StoreError::not_found(format!(
+                    "Track '{}' does not belong to release '{}'",
+                    track_id, release_id
+                )))
+            }
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch track from 'tracks' table: {}",
+                    err
+                )))
+            }
+        };
+
+        // The disc of vinyl tracks follows from their side, so the current disc is only kept for other media
+        let media_type = Self::media_type(tx, release_id).await?;
+        let disc = match (track.position.disc, &media_type) {
+            (Some(disc), _) => Some(disc),
+            (None, MediaType::Vinyl) => None,
+            (None, _) => Some(current.disc),
+        };
+        let side = track.position.side.or_else(|| current.record_side.clone());
+
+        let (disc, side) = match TrackPosition::normalize_side(&media_type, disc, side.as_deref()) {
+            Ok(position) => position,
+            Err(err) => return Err(StoreError::invalid(err)),
+        };
+
+        let moved = disc != current.disc || side != current.record_side;
+        let index = if moved || track.position.index.is_some() {
+            // Close the gap on the old side, then open a slot on the new side
+            Self::shift_side_tracks(
+                tx,
+                release_id,
+                current.disc,
+                current.record_side.as_deref(),
+                current.position + 1,
+                -1,
+                track_id,
+            )
+            .await?;
+
+            let count =
+                Self::count_side_tracks(tx, release_id, disc, side.as_deref(), track_id).await?;
+            let index = Self::slot_index(track.position.index, count)?;
+            Self::shift_side_tracks(tx, release_id, disc, side.as_deref(), index, 1, track_id)
+                .await?;
+            index
+        } else {
+            current.position
+        };
+
+        match query_as!(
+            TracksJoin,
+            r#"
+                UPDATE tracks SET
+                    title = COALESCE($3, title),
+                    duration = COALESCE($4, duration),
+                    disc = $5,
+                    record_side = $6,
+                    position = $7,
+                    digital = COALESCE($8, digital)
+                WHERE id = $1 AND belongs_to = $2
+                RETURNING
+                    id as track_id, title as track_title, duration as track_duration, disc as track_disc,
+                    record_side as track_record_side, position as track_position, digital as track_digital,
+                    belongs_to as release_id
+            "#,
+            track_id,
+            release_id,
+            track.title,
+            track.duration.map(|d| d.0),
+            disc,
+            side,
+            index,
+            track.digital
+        )
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(row) => {
+                let mut row = Track::from(row);
+                row.links = match track.links {
+                    Some(links) => Self::set_links(tx, LinkOwner::Track, track_id, links).await?,
+                    None => {
+                        let mut links =
+                            Self::fetch_links(&mut *tx, LinkOwner::Track, &[track_id.to_string()])
+                                .await?;
+                        links.remove(track_id).unwrap_or_default()
+                    }
+                };
+                Ok(row)
+            }
+            Err(err) => Err(StoreError::new(format!("Failed to update 'tracks' table: {}", err))),
+        }
+    }
+
+    pub(super) async fn remove_track(
+        tx: &mut Transaction<'_, Postgres>,
+        release_id: &str,
+        track_id: &str,
+    ) -> Result<(), StoreError> {
+        let removed = match query!(
+            "DELETE FROM tracks WHERE id = $1 AND belongs_to = $2 RETURNING disc, record_side, position",
+            track_id,
+            release_id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(removed) => removed,
+            Err(sqlx::Error::RowNotFound) => {
+                return Err(StoreError::not_found(format!(
+                    "Track '{}' does not belong to release '{}'",
+                    track_id, release_id
+                )))
+            }
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to delete from 'tracks' table: {}",
+                    err
+                )))
+            }
+        };
+
+        Self::delete_links(tx, LinkOwner::Track, &[track_id.to_string()]).await?;
+        Self::shift_side_tracks(
+            tx,
+            release_id,
+            removed.disc,
+            removed.record_side.as_deref(),
+            removed.position + 1,
+            -1,
+            track_id,
+        )
+        .await
+    }
+
+    /// Deletes all tracks of the release with `release_id` together with their links.
+    pub(super) async fn remove_release_tracks(
+        tx: &mut Transaction<'_, Postgres>,
+        release_id: &str,
+    ) -> Result<(), StoreError> {
+        let track_ids = match query_scalar!(
+            "DELETE FROM tracks WHERE belongs_to = $1 RETURNING id",
+            release_id
+        )
+        .fetch_all(&mut *tx)
+        .await
+        {
+            Ok(track_ids) => track_ids,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to delete from 'tracks' table: {}",
+                    err
+                )))
+            }
+        };
+
+        Self::delete_links(tx, LinkOwner::Track, &track_ids).await
+    }
+
+    /// Returns the tracks of the media entry with `media_id`, ordered by disc, side and index. Tracks belong to the
+    /// release of the media entry, so all of its copies share them.
+    pub async fn get_tracks(&self, media_id: String) -> Result<Vec<Track>, StoreError> {
+        let release_id = match query_scalar!("SELECT release_id FROM media WHERE id = $1", media_id)
+            .fetch_one(&self.pool)
+            .await
+        {
+            Ok(release_id) => release_id,
+            Err(sqlx::Error::RowNotFound) => {
+                return Err(StoreError::not_found(format!(
+                    "Media entry '{}' does not exist",
+                    media_id
+                )))
+            }
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to look up media entry: {}",
+                    err
+                )))
+            }
+        };
+
+        let tracks = match query_as!(
+            TracksJoin,
+            r#"
+                SELECT
+                    tracks.id as track_id, tracks.title as track_title, tracks.duration as track_duration,
+                    tracks.disc as track_disc, tracks.record_side as track_record_side,
+                    tracks.position as track_position, tracks.digital as track_digital,
+                    tracks.belongs_to as release_id
+                FROM tracks
+                WHERE tracks.belongs_to = $1
+                ORDER BY tracks.disc, tracks.record_side, tracks.position
+            "#,
+            release_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(tracks) => tracks,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch tracks from 'tracks' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let track_ids = tracks
+            .iter()
+            .map(|t| t.track_id.clone())
+            .collect::<Vec<String>>();
+        let mut links = Self::fetch_links(&self.pool, LinkOwner::Track, &track_ids).await?;
+
+        Ok(tracks
+            .into_iter()
+            .map(|t| {
+                let mut track = Track::from(t);
+                track.links = links.remove(&track.id).unwrap_or_default();
+                track
+            })
+            .collect())
+    }
+
+    /// Replaces all tracks of the release of the media entry with `media_id` with `tracks`. Tracks without an explicit
+    /// index are numbered in the order they are given.
+    pub async fn replace_tracks(
+        &self,
+        media_id: String,
+        tracks: Vec<CreateTrackRequest>,
+    ) -> Result<Vec<Track>, StoreError> {
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        let release_id = Self::release_of(&mut tx, &media_id).await?;
+        Self::touch_release(&mut tx, &release_id).await?;
+
+        Self::remove_release_tracks(&mut tx, &release_id).await?;
+
+        for track in tracks {
+            Self::insert_track(&mut tx, &release_id, track).await?;
+        }
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        self.get_tracks(media_id).await
+    }
+
+    pub async fn add_track(
+        &self,
+        media_id: String,
+        track: CreateTrackRequest,
+    ) -> Result<Track, StoreError> {
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        let release_id = Self::release_of(&mut tx, &media_id).await?;
+        Self::touch_release(&mut tx, &release_id).await?;
+        let track = Self::insert_track(&mut tx, &release_id, track).await?;
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        Ok(track)
+    }
+
+    pub async fn update_track(
+        &self,
+        media_id: String,
+        track_id: String,
+        track: UpdateTrackRequest,
+    ) -> Result<Track, StoreError> {
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        let release_id = Self::release_of(&mut tx, &media_id).await?;
+        Self::touch_release(&mut tx, &release_id).await?;
+        let track = Self::update_track_in_tx(&mut tx, &release_id, &track_id, track).await?;
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        Ok(track)
+    }
+
+    pub async fn delete_track(&self, media_id: String, track_id: String) -> Result<(), StoreError> {
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        let release_id = Self::release_of(&mut tx, &media_id).await?;
+        Self::touch_release(&mut tx, &release_id).await?;
+        Self::remove_track(&mut tx, &release_id, &track_id).await?;
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        Ok(())
+    }
+}
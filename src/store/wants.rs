@@ -0,0 +1,622 @@
+use nanoid::nanoid;
+use sqlx::{query, query_as, query_scalar, Postgres, Transaction};
+
+use crate::{
+    store::{
+        error::StoreError,
+        models::{ArtistsJoin, LabelsJoin, WantsJoin},
+        Store,
+    },
+    types::{
+        Amount, ArtistRef, ConvertWantRequest, CreateMediaRequest, CreateWantRequest, Grade,
+        LabelRef, LinkOwner, Media, MediaLabelRef, MediaType, Price, UpdateWantRequest, Want,
+        WantPriority,
+    },
+};
+
+impl Store {
+    /// Adds artists and labels to the raw `wants`.
+    async fn complete_wants(&self, wants: Vec<WantsJoin>) -> Result<Vec<Want>, StoreError> {
+        let want_ids = wants
+            .iter()
+            .map(|w| w.want_id.clone())
+            .collect::<Vec<String>>();
+
+        let artists = match query_as!(
+            ArtistsJoin,
+            r#"
+                SELECT
+                    artists.id as artist_id, artists.name as artist_name, wants_artists_rel.want_id as owner_id
+                FROM wants_artists_rel
+                JOIN artists ON (artists.id = wants_artists_rel.artist_id)
+                WHERE wants_artists_rel.want_id = ANY($1)
+                ORDER BY wants_artists_rel.id
+            "#,
+            &want_ids[..]
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(artists) => artists,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch artists from 'artists' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let artist_ids = artists
+            .iter()
+            .map(|a| a.artist_id.clone())
+            .collect::<Vec<String>>();
+        let artist_links = Self::fetch_links(&self.pool, LinkOwner::Artist, &artist_ids).await?;
+        let mut artists = Self::group_artists_by_owner_id(artists, &artist_links);
+
+        let labels = match query_as!(
+            LabelsJoin,
+            r#"
+                SELECT
+                    labels.id as label_id, labels.name as label_name, labels.label_code as label_label_code,
+                    wants_label_rel.catalogue as catalogue, wants_label_rel.want_id as owner_id
+                FROM wants_label_rel
+                JOIN labels ON (labels.id = wants_label_rel.label_id)
+                WHERE wants_label_rel.want_id = ANY($1)
+                ORDER BY wants_label_rel.id
+            "#,
+            &want_ids[..]
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(labels) => labels,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch labels from 'labels' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let label_ids = labels
+            .iter()
+            .map(|l| l.label_id.clone())
+            .collect::<Vec<String>>();
+        let label_links = Self::fetch_links(&self.pool, LinkOwner::Label, &label_ids).await?;
+        let mut labels = Self::group_labels_by_owner_id(labels, &label_links);
+
+        Ok(wants
+            .into_iter()
+            .map(|w| Want {
+                artists: artists.remove(&w.want_id).unwrap_or_default(),
+                labels: labels.remove(&w.want_id).unwrap_or_default(),
+                id: w.want_id,
+                title: w.want_title,
+                media_type: w.want_media_type,
+                priority: w.want_priority,
+                max_price: w.want_max_price.zip(w.want_max_price_currency).map(
+                    |(amount, currency)| Price {
+                        amount: Amount::new(amount, &currency),
+                        currency,
+                    },
+                ),
+                min_condition: w.want_min_condition.map(Grade::from),
+                notes: w.want_notes,
+                created_at: w.want_created_at,
+                modified_at: w.want_modified_at,
+            })
+            .collect())
+    }
+
+    /// Returns a page of wants together with the total number of matching wants. Wants are ordered by priority,
+    /// the most recently added first within the same priority.
+    pub async fn get_wants(
+        &self,
+        limit: i64,
+        offset: i64,
+        priority: Option<WantPriority>,
+    ) -> Result<(Vec<Want>, i64), StoreError> {
+        let total = match query_scalar!(
+            r#"SELECT COUNT(*) as "count!" FROM wants WHERE $1::VARCHAR IS NULL OR priority = $1"#,
+            priority as _
+        )
+        .fetch_one(&self.pool)
+        .await
+        {
+            Ok(total) => total,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to count wants in 'wants' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let wants = match query_as!(
+            WantsJoin,
+            r#"
+                SELECT
+                    id as want_id, title as want_title, media_type as "want_media_type: _",
+                    priority as "want_priority: _", max_price as want_max_price,
+                    max_price_currency as want_max_price_currency, min_condition as "want_min_condition: _",
+                    notes as want_notes, created_at as want_created_at, modified_at as want_modified_at
+                FROM wants
+                WHERE $1::VARCHAR IS NULL OR priority = $1
+                ORDER BY CASE priority WHEN 'High' THEN 0 WHEN 'Normal' THEN 1 ELSE 2 END, created_at DESC, id
+                LIMIT $2 OFFSET $3
+            "#,
+            priority as _,
+            limit,
+            offset
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(wants) => wants,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch wants from 'wants' table: {}",
+                    err
+                )))
+            }
+        };
+
+        Ok((self.complete_wants(wants).await?, total))
+    }
+
+    pub async fn get_want(&self, id: String) -> Result<Want, StoreError> {
+        let want = match query_as!(
+            WantsJoin,
+            r#"
+                SELECT
+                    id as want_id, title as want_title, media_type as "want_media_type: _",
+                    priority as "want_priority: _", max_price as want_max_price,
+                    max_price_currency as want_max_price_currency, min_condition as "want_min_condition: _",
+                    notes as want_notes, created_at as want_created_at, modified_at as want_modified_at
+                FROM wants
+                WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        {
+            Ok(Some(want)) => want,
+            Ok(None) => {
+                return Err(StoreError::not_found(format!(
+                    "Want '{}' does not exist",
+                    id
+                )))
+            }
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch want from 'wants' table: {}",
+                    err
+                )))
+            }
+        };
+
+        match self.complete_wants(vec![want]).await?.pop() {
+            Some(want) => Ok(want),
+            None => Err(StoreError::new("Failed to complete want")),
+        }
+    }
+
+    /// Creates a new want including its artists and labels in a single transaction.
+    pub async fn create_want(&self, want: CreateWantRequest) -> Result<Want, StoreError> {
+        let max_price = match want.max_price.map(|p| p.normalize()).transpose() {
+            Ok(max_price) => max_price,
+            Err(err) => return Err(StoreError::invalid(err)),
+        };
+
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        let want_id = nanoid!();
+        let now = Self::now();
+        let (amount, currency) = match max_price {
+            Some(price) => (Some(price.amount.minor), Some(price.currency)),
+            None => (None, None),
+        };
+
+        if let Err(err) = query!(
+            r#"
+                INSERT INTO wants (
+                    id, title, media_type, priority, max_price, max_price_currency, min_condition, notes,
+                    created_at, modified_at
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#,
+            want_id,
+            want.title,
+            want.media_type as _,
+            want.priority as _,
+            amount,
+            currency,
+            want.min_condition as _,
+            want.notes,
+            now,
+            now
+        )
+        .execute(&mut tx)
+        .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to insert into 'wants' table: {}",
+                err
+            )));
+        }
+
+        Self::set_want_artists(&mut tx, &want_id, want.artists).await?;
+        Self::set_want_labels(&mut tx, &want_id, want.labels).await?;
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        self.get_want(want_id).await
+    }
+
+    /// Applies a sparse update to the want with `id`. Fields which are not set in `want` are left untouched,
+    /// `modified_at` is always bumped.
+    pub async fn update_want(
+        &self,
+        id: String,
+        want: UpdateWantRequest,
+    ) -> Result<Want, StoreError> {
+        let max_price = match want.max_price.map(|p| p.map(|p| p.normalize()).transpose()) {
+            Some(Ok(max_price)) => Some(max_price),
+            Some(Err(err)) => return Err(StoreError::invalid(err)),
+            None => None,
+        };
+
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        let (amount, currency) = match max_price.clone().flatten() {
+            Some(price) => (Some(price.amount.minor), Some(price.currency)),
+            None => (None, None),
+        };
+
+        let result = match query!(
+            r#"
+                UPDATE wants SET
+                    title = COALESCE($2, title),
+                    media_type = COALESCE($3, media_type),
+                    priority = COALESCE($4, priority),
+                    max_price = CASE WHEN $5 THEN $6 ELSE max_price END,
+                    max_price_currency = CASE WHEN $5 THEN $7 ELSE max_price_currency END,
+                    min_condition = CASE WHEN $8 THEN $9 ELSE min_condition END,
+                    notes = COALESCE($10, notes),
+                    modified_at = $11
+                WHERE id = $1
+            "#,
+            id,
+            want.title,
+            want.media_type as _,
+            want.priority as _,
+            max_price.is_some(),
+            amount,
+            currency,
+            want.min_condition.is_some(),
+            want.min_condition.flatten() as _,
+            want.notes,
+            Self::now()
+        )
+        .execute(&mut tx)
+        .await
+        {
+            Ok(result) => result,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to update 'wants' table: {}",
+                    err
+                )))
+            }
+        };
+
+        if result.rows_affected() == 0 {
+            return Err(StoreError::not_found(format!(
+                "Want '{}' does not exist",
+                id
+            )));
+        }
+
+        if let Some(artists) = want.artists {
+            Self::set_want_artists(&mut tx, &id, artists).await?;
+        }
+
+        if let Some(labels) = want.labels {
+            Self::set_want_labels(&mut tx, &id, labels).await?;
+        }
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        self.get_want(id).await
+    }
+
+    pub async fn delete_want(&self, id: String) -> Result<(), StoreError> {
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        Self::remove_want(&mut tx, &id).await?;
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Turns the want with `id` into a media entry and removes the want. Both happen in a single transaction, so the
+    /// want is only gone if the media entry was created.
+    pub async fn convert_want(
+        &self,
+        id: String,
+        media: ConvertWantRequest,
+    ) -> Result<Media, StoreError> {
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        let want = match query!(
+            r#"SELECT title, media_type as "media_type: MediaType", notes FROM wants WHERE id = $1 FOR UPDATE"#,
+            id
+        )
+        .fetch_optional(&mut tx)
+        .await
+        {
+            Ok(Some(want)) => want,
+            Ok(None) => {
+                return Err(StoreError::not_found(format!(
+                    "Want '{}' does not exist",
+                    id
+                )))
+            }
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch want from 'wants' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let artists = match query_scalar!(
+            "SELECT artist_id FROM wants_artists_rel WHERE want_id = $1 ORDER BY id",
+            id
+        )
+        .fetch_all(&mut tx)
+        .await
+        {
+            Ok(artists) => artists,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch artists from 'wants_artists_rel' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let labels = match query!(
+            "SELECT label_id, catalogue FROM wants_label_rel WHERE want_id = $1 ORDER BY id",
+            id
+        )
+        .fetch_all(&mut tx)
+        .await
+        {
+            Ok(labels) => labels,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to fetch labels from 'wants_label_rel' table: {}",
+                    err
+                )))
+            }
+        };
+
+        let media_id = Self::insert_media(
+            &mut tx,
+            CreateMediaRequest {
+                title: want.title,
+                media_type: want.media_type,
+                format: media.format,
+                pressing: media.pressing,
+                artists: artists
+                    .into_iter()
+                    .map(|id| ArtistRef::Existing { id })
+                    .collect(),
+                labels: labels
+                    .into_iter()
+                    .map(|l| MediaLabelRef {
+                        label: LabelRef::Existing { id: l.label_id },
+                        catalogue: l.catalogue,
+                    })
+                    .collect(),
+                tracks: media.tracks,
+                release_date: media.release_date,
+                purchase_date: media.purchase_date,
+                purchase: media.purchase,
+                media_condition: media.media_condition,
+                sleeve_condition: media.sleeve_condition,
+                bought: media.bought,
+                notes: media.notes.unwrap_or(want.notes),
+                links: media.links,
+            },
+        )
+        .await?;
+
+        Self::remove_want(&mut tx, &id).await?;
+
+        if let Err(err) = tx.commit().await {
+            return Err(StoreError::new(format!(
+                "Failed to commit transaction: {}",
+                err
+            )));
+        }
+
+        self.get_media_entry(media_id).await
+    }
+
+    /// Deletes the want with `id` and its artist and label relations. Artists and labels themselves are kept.
+    async fn remove_want(tx: &mut Transaction<'_, Postgres>, id: &str) -> Result<(), StoreError> {
+        let result = match query!("DELETE FROM wants WHERE id = $1", id)
+            .execute(&mut *tx)
+            .await
+        {
+            Ok(result) => result,
+            Err(err) => {
+                return Err(StoreError::new(format!(
+                    "Failed to delete from 'wants' table: {}",
+                    err
+                )))
+            }
+        };
+
+        if result.rows_affected() == 0 {
+            return Err(StoreError::not_found(format!(
+                "Want '{}' does not exist",
+                id
+            )));
+        }
+
+        if let Err(err) = query!("DELETE FROM wants_artists_rel WHERE want_id = $1", id)
+            .execute(&mut *tx)
+            .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to delete from 'wants_artists_rel' table: {}",
+                err
+            )));
+        }
+
+        if let Err(err) = query!("DELETE FROM wants_label_rel WHERE want_id = $1", id)
+            .execute(&mut *tx)
+            .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to delete from 'wants_label_rel' table: {}",
+                err
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the artists of the want with `want_id`. New artists are created first.
+    async fn set_want_artists(
+        tx: &mut Transaction<'_, Postgres>,
+        want_id: &str,
+        artists: Vec<ArtistRef>,
+    ) -> Result<(), StoreError> {
+        if let Err(err) = query!("DELETE FROM wants_artists_rel WHERE want_id = $1", want_id)
+            .execute(&mut *tx)
+            .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to delete from 'wants_artists_rel' table: {}",
+                err
+            )));
+        }
+
+        for artist in artists {
+            let artist_id = Self::resolve_artist(tx, artist).await?;
+
+            if let Err(err) = query!(
+                r#"
+                    INSERT INTO wants_artists_rel (want_id, artist_id) VALUES ($1, $2)
+                    ON CONFLICT (want_id, artist_id) DO NOTHING
+                "#,
+                want_id,
+                artist_id
+            )
+            .execute(&mut *tx)
+            .await
+            {
+                return Err(StoreError::new(format!(
+                    "Failed to insert into 'wants_artists_rel' table: {}",
+                    err
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the labels of the want with `want_id`. New labels are created first.
+    async fn set_want_labels(
+        tx: &mut Transaction<'_, Postgres>,
+        want_id: &str,
+        labels: Vec<MediaLabelRef>,
+    ) -> Result<(), StoreError> {
+        if let Err(err) = query!("DELETE FROM wants_label_rel WHERE want_id = $1", want_id)
+            .execute(&mut *tx)
+            .await
+        {
+            return Err(StoreError::new(format!(
+                "Failed to delete from 'wants_label_rel' table: {}",
+                err
+            )));
+        }
+
+        for label in labels {
+            let label_id = Self::resolve_label(tx, label.label).await?;
+
+            if let Err(err) = query!(
+                r#"
+                    INSERT INTO wants_label_rel (want_id, label_id, catalogue) VALUES ($1, $2, $3)
+                    ON CONFLICT (want_id, label_id) DO UPDATE SET catalogue = EXCLUDED.catalogue
+                "#,
+                want_id,
+                label_id,
+                label.catalogue
+            )
+            .execute(&mut *tx)
+            .await
+            {
+                return Err(StoreError::new(format!(
+                    "Failed to insert into 'wants_label_rel' table: {}",
+                    err
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
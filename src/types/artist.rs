@@ -1,8 +1,173 @@
-use serde::Serialize;
+use axum::{response::IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::types::{Link, LinkRequest};
 
 #[derive(Serialize, Clone)]
 pub struct Artist {
     pub id: String,
     pub name: String,
-    pub urls: String,
+    pub links: Vec<Link>,
+}
+
+/// An artist referenced in a media payload. Either points to an already existing artist by its ID or describes a new
+/// artist which gets created alongside the media entry.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum ArtistRef {
+    Existing {
+        id: String,
+    },
+    New {
+        name: String,
+        #[serde(default)]
+        links: Vec<LinkRequest>,
+    },
+}
+
+/// Changes to the artists of a media entry. The list is either replaced completely via `set` or edited one artist at
+/// a time via `add` and `remove`.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct ArtistsPatch {
+    pub set: Option<Vec<ArtistRef>>,
+    pub add: Vec<ArtistRef>,
+    pub remove: Vec<String>,
+}
+
+impl ArtistsPatch {
+    pub fn is_empty(&self) -> bool {
+        self.set.is_none() && self.add.is_empty() && self.remove.is_empty()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct GetArtistsQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateArtistRequest {
+    pub name: String,
+    #[serde(default)]
+    pub links: Vec<LinkRequest>,
+}
+
+/// A sparse update of an artist. Omitted fields keep their current value.
+#[derive(Deserialize)]
+pub struct UpdateArtistRequest {
+    pub name: Option<String>,
+    /// Replaces all links of the artist.
+    pub links: Option<Vec<LinkRequest>>,
+}
+
+#[derive(Serialize)]
+pub struct GetArtistsResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    artists: Option<Vec<Artist>>,
+}
+
+impl GetArtistsResponse {
+    pub fn success(artists: Vec<Artist>, total: i64) -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+            total: Some(total),
+            artists: Some(artists),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            total: None,
+            artists: None,
+        }
+    }
+}
+
+impl IntoResponse for GetArtistsResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
+
+/// Response of the endpoints which return a single artist, i.e. get, create and update.
+#[derive(Serialize)]
+pub struct ArtistResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    artist: Option<Artist>,
+}
+
+impl ArtistResponse {
+    pub fn success(artist: Artist) -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+            artist: Some(artist),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            artist: None,
+        }
+    }
+}
+
+impl IntoResponse for ArtistResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
+
+#[derive(Serialize)]
+pub struct DeleteArtistResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl DeleteArtistResponse {
+    pub fn success() -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+        }
+    }
+}
+
+impl IntoResponse for DeleteArtistResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
 }
@@ -1,19 +1,408 @@
-use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use sqlx::Type;
 
 #[derive(Serialize, Deserialize, Type, Clone)]
+#[sqlx(type_name = "varchar")]
 pub enum BuyCondition {
     New,
     Used,
 }
 
-#[derive(Serialize, Deserialize, Type, Clone)]
+/// The canonical condition of a record or sleeve. Conditions are stored in this form and can be read and written in
+/// any [`GradingScale`]. The grade codes of the scales map to the canonical conditions as follows:
+///
+/// | Canonical       | Goldmine  | Record Collector |
+/// |-----------------|-----------|------------------|
+/// | `Mint`          | `M`       | `M`              |
+/// | `NearMint`      | `NM`      | `EX+`            |
+/// | `NearMintMinus` | `NM-`     | `EX`             |
+/// | `VeryGoodPlus`  | `VG+`     | `VG+`            |
+/// | `VeryGood`      | `VG`      | `VG`             |
+/// | `VeryGoodMinus` | `VG-`     | `VG-`            |
+/// | `GoodPlus`      | `G+`      | `G+`             |
+/// | `Good`          | `G`       | `G`              |
+/// | `GoodMinus`     | `G-`      | `G-`             |
+/// | `Fair`          | `F`       | `F`              |
+/// | `Poor`          | `P`       | `P`              |
+/// | `Generic`       | `Generic` | `Generic`        |
+///
+/// Grade codes are accepted case-insensitively regardless of the scale, as every code means the same in both
+/// scales. The Record Collector grades `EX-` and `B` (Bad) are accepted as `VeryGoodPlus` and `Poor`. The written-out
+/// grade names of the scales are accepted as well, see [`GradingScale`].
+#[derive(Serialize, Type, Debug, Clone, Copy, PartialEq, Eq)]
+#[sqlx(type_name = "varchar")]
 pub enum Condition {
     Mint,
     NearMint,
+    NearMintMinus,
     VeryGoodPlus,
     VeryGood,
+    VeryGoodMinus,
+    GoodPlus,
     Good,
+    GoodMinus,
+    Fair,
     Poor,
     Generic,
 }
+
+impl Condition {
+    pub const ALL: [Condition; 12] = [
+        Self::Mint,
+        Self::NearMint,
+        Self::NearMintMinus,
+        Self::VeryGoodPlus,
+        Self::VeryGood,
+        Self::VeryGoodMinus,
+        Self::GoodPlus,
+        Self::Good,
+        Self::GoodMinus,
+        Self::Fair,
+        Self::Poor,
+        Self::Generic,
+    ];
+
+    /// Returns the rank of the condition, higher is better. `Generic` ranks below all other conditions.
+    fn rank(&self) -> u8 {
+        match self {
+            Self::Mint => 11,
+            Self::NearMint => 10,
+            Self::NearMintMinus => 9,
+            Self::VeryGoodPlus => 8,
+            Self::VeryGood => 7,
+            Self::VeryGoodMinus => 6,
+            Self::GoodPlus => 5,
+            Self::Good => 4,
+            Self::GoodMinus => 3,
+            Self::Fair => 2,
+            Self::Poor => 1,
+            Self::Generic => 0,
+        }
+    }
+
+    /// Returns the name of the condition as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Mint => "Mint",
+            Self::NearMint => "NearMint",
+            Self::NearMintMinus => "NearMintMinus",
+            Self::VeryGoodPlus => "VeryGoodPlus",
+            Self::VeryGood => "VeryGood",
+            Self::VeryGoodMinus => "VeryGoodMinus",
+            Self::GoodPlus => "GoodPlus",
+            Self::Good => "Good",
+            Self::GoodMinus => "GoodMinus",
+            Self::Fair => "Fair",
+            Self::Poor => "Poor",
+            Self::Generic => "Generic",
+        }
+    }
+
+    /// Returns the grade code of the condition in the `scale`, see the mapping above.
+    pub fn code(&self, scale: GradingScale) -> &'static str {
+        match (self, scale) {
+            (_, GradingScale::Canonical) => self.as_str(),
+            (Self::NearMint, GradingScale::RecordCollector) => "EX+",
+            (Self::NearMintMinus, GradingScale::RecordCollector) => "EX",
+            (Self::Mint, _) => "M",
+            (Self::NearMint, _) => "NM",
+            (Self::NearMintMinus, _) => "NM-",
+            (Self::VeryGoodPlus, _) => "VG+",
+            (Self::VeryGood, _) => "VG",
+            (Self::VeryGoodMinus, _) => "VG-",
+            (Self::GoodPlus, _) => "G+",
+            (Self::Good, _) => "G",
+            (Self::GoodMinus, _) => "G-",
+            (Self::Fair, _) => "F",
+            (Self::Poor, _) => "P",
+            (Self::Generic, _) => "Generic",
+        }
+    }
+
+    /// Returns all conditions which are at least as good as this condition.
+    pub fn at_least(&self) -> Vec<Condition> {
+        Self::ALL.into_iter().filter(|c| c >= self).collect()
+    }
+}
+
+/// The written-out grade names of the scales, see [`GradingScale`]. Names are matched case-insensitively and with any
+/// whitespace between their words.
+const GRADE_NAMES: [(&str, Condition); 23] = [
+    ("MINT", Condition::Mint),
+    ("NEAR MINT", Condition::NearMint),
+    ("EXCELLENT PLUS", Condition::NearMint),
+    ("EXCELLENT+", Condition::NearMint),
+    ("NEAR MINT MINUS", Condition::NearMintMinus),
+    ("NEAR MINT-", Condition::NearMintMinus),
+    ("EXCELLENT", Condition::NearMintMinus),
+    ("VERY GOOD PLUS", Condition::VeryGoodPlus),
+    ("VERY GOOD+", Condition::VeryGoodPlus),
+    ("EXCELLENT MINUS", Condition::VeryGoodPlus),
+    ("EXCELLENT-", Condition::VeryGoodPlus),
+    ("VERY GOOD", Condition::VeryGood),
+    ("VERY GOOD MINUS", Condition::VeryGoodMinus),
+    ("VERY GOOD-", Condition::VeryGoodMinus),
+    ("GOOD PLUS", Condition::GoodPlus),
+    ("GOOD+", Condition::GoodPlus),
+    ("GOOD", Condition::Good),
+    ("GOOD MINUS", Condition::GoodMinus),
+    ("GOOD-", Condition::GoodMinus),
+    ("FAIR", Condition::Fair),
+    ("POOR", Condition::Poor),
+    ("BAD", Condition::Poor),
+    ("GENERIC", Condition::Generic),
+];
+
+impl FromStr for Condition {
+    type Err = String;
+
+    /// Parses a canonical condition name, a grade code or a written-out grade name of any scale.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(condition) = Self::ALL
+            .into_iter()
+            .find(|c| c.as_str().eq_ignore_ascii_case(s.trim()))
+        {
+            return Ok(condition);
+        }
+
+        let name = s.split_whitespace().collect::<Vec<&str>>().join(" ");
+        if let Some((_, condition)) = GRADE_NAMES
+            .into_iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(&name))
+        {
+            return Ok(condition);
+        }
+
+        match s.trim().to_uppercase().as_str() {
+            "M" => Ok(Self::Mint),
+            "NM" | "EX+" => Ok(Self::NearMint),
+            "NM-" | "EX" => Ok(Self::NearMintMinus),
+            "VG+" | "EX-" => Ok(Self::VeryGoodPlus),
+            "VG" => Ok(Self::VeryGood),
+            "VG-" => Ok(Self::VeryGoodMinus),
+            "G+" => Ok(Self::GoodPlus),
+            "G" => Ok(Self::Good),
+            "G-" => Ok(Self::GoodMinus),
+            "F" => Ok(Self::Fair),
+            "P" | "B" => Ok(Self::Poor),
+            "GENERIC" => Ok(Self::Generic),
+            _ => Err(format!("Unknown condition '{}'", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Condition {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+/// A scale conditions are read and written in. `Canonical` uses the names of [`Condition`]. Conditions are written as
+/// the grade codes of the scale, see [`Condition`], and can also be read as the written-out grade names of any scale:
+///
+/// | Canonical       | Goldmine                           | Record Collector                   |
+/// |-----------------|------------------------------------|------------------------------------|
+/// | `Mint`          | `Mint`                             | `Mint`                             |
+/// | `NearMint`      | `Near Mint`                        | `Excellent Plus`, `Excellent+`     |
+/// | `NearMintMinus` | `Near Mint Minus`, `Near Mint-`    | `Excellent`                        |
+/// | `VeryGoodPlus`  | `Very Good Plus`, `Very Good+`     | `Excellent Minus`, `Excellent-`    |
+/// | `VeryGood`      | `Very Good`                        | `Very Good`                        |
+/// | `VeryGoodMinus` | `Very Good Minus`, `Very Good-`    | `Very Good Minus`, `Very Good-`    |
+/// | `GoodPlus`      | `Good Plus`, `Good+`               | `Good Plus`, `Good+`               |
+/// | `Good`          | `Good`                             | `Good`                             |
+/// | `GoodMinus`     | `Good Minus`, `Good-`              | `Good Minus`, `Good-`              |
+/// | `Fair`          | `Fair`                             | `Fair`                             |
+/// | `Poor`          | `Poor`                             | `Poor`, `Bad`                      |
+/// | `Generic`       | `Generic`                          | `Generic`                          |
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GradingScale {
+    #[default]
+    Canonical,
+    Goldmine,
+    RecordCollector,
+}
+
+/// Query parameter selecting the grading scale conditions are returned in.
+#[derive(Deserialize, Default)]
+pub struct GradingScaleQuery {
+    #[serde(default)]
+    pub scale: GradingScale,
+}
+
+/// A condition presented in a grading scale. It is serialized as the grade code of the scale.
+#[derive(Clone, Copy)]
+pub struct Grade {
+    pub condition: Condition,
+    pub scale: GradingScale,
+}
+
+impl From<Condition> for Grade {
+    fn from(condition: Condition) -> Self {
+        Self {
+            condition,
+            scale: GradingScale::Canonical,
+        }
+    }
+}
+
+impl Serialize for Grade {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.condition.code(self.scale))
+    }
+}
+
+impl PartialOrd for Condition {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Condition {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCALES: [GradingScale; 3] = [
+        GradingScale::Canonical,
+        GradingScale::Goldmine,
+        GradingScale::RecordCollector,
+    ];
+
+    #[test]
+    fn round_trips_codes_of_every_scale() {
+        for scale in SCALES {
+            for condition in Condition::ALL {
+                assert_eq!(
+                    condition.code(scale).parse::<Condition>(),
+                    Ok(condition),
+                    "code '{}' does not round trip",
+                    condition.code(scale)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn codes_are_unique_within_a_scale() {
+        for scale in SCALES {
+            for (i, a) in Condition::ALL.iter().enumerate() {
+                for b in &Condition::ALL[i + 1..] {
+                    assert_ne!(a.code(scale), b.code(scale));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn maps_record_collector_codes() {
+        assert_eq!(
+            Condition::NearMint.code(GradingScale::RecordCollector),
+            "EX+"
+        );
+        assert_eq!(
+            Condition::NearMintMinus.code(GradingScale::RecordCollector),
+            "EX"
+        );
+        assert_eq!(Condition::NearMint.code(GradingScale::Goldmine), "NM");
+        assert_eq!("EX+".parse::<Condition>(), Ok(Condition::NearMint));
+        assert_eq!("EX".parse::<Condition>(), Ok(Condition::NearMintMinus));
+    }
+
+    #[test]
+    fn accepts_aliases() {
+        assert_eq!("EX-".parse::<Condition>(), Ok(Condition::VeryGoodPlus));
+        assert_eq!("B".parse::<Condition>(), Ok(Condition::Poor));
+    }
+
+    #[test]
+    fn accepts_grade_names() {
+        for (name, condition) in [
+            ("Mint", Condition::Mint),
+            ("Near Mint", Condition::NearMint),
+            ("Excellent Plus", Condition::NearMint),
+            ("Excellent+", Condition::NearMint),
+            ("Near Mint Minus", Condition::NearMintMinus),
+            ("Near Mint-", Condition::NearMintMinus),
+            ("Excellent", Condition::NearMintMinus),
+            ("Very Good Plus", Condition::VeryGoodPlus),
+            ("Very Good+", Condition::VeryGoodPlus),
+            ("Excellent Minus", Condition::VeryGoodPlus),
+            ("Excellent-", Condition::VeryGoodPlus),
+            ("Very Good", Condition::VeryGood),
+            ("Very Good Minus", Condition::VeryGoodMinus),
+            ("Good Plus", Condition::GoodPlus),
+            ("Good", Condition::Good),
+            ("Good-", Condition::GoodMinus),
+            ("Fair", Condition::Fair),
+            ("Poor", Condition::Poor),
+            ("Bad", Condition::Poor),
+            ("Generic", Condition::Generic),
+        ] {
+            assert_eq!(name.parse::<Condition>(), Ok(condition), "'{}'", name);
+        }
+    }
+
+    #[test]
+    fn parses_case_insensitively() {
+        assert_eq!("vg+".parse::<Condition>(), Ok(Condition::VeryGoodPlus));
+        assert_eq!("nm-".parse::<Condition>(), Ok(Condition::NearMintMinus));
+        assert_eq!("ex-".parse::<Condition>(), Ok(Condition::VeryGoodPlus));
+        assert_eq!("nearmint".parse::<Condition>(), Ok(Condition::NearMint));
+        assert_eq!(" generic ".parse::<Condition>(), Ok(Condition::Generic));
+        assert_eq!(
+            "EXCELLENT".parse::<Condition>(),
+            Ok(Condition::NearMintMinus)
+        );
+        assert_eq!(
+            " near   mint ".parse::<Condition>(),
+            Ok(Condition::NearMint)
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_conditions() {
+        for input in [
+            "",
+            "VG++",
+            "NM+",
+            "A",
+            "Excellent Plus Plus",
+            "Nearmint Minus",
+            "Very",
+            "E",
+            "VG +",
+        ] {
+            assert!(
+                input.parse::<Condition>().is_err(),
+                "'{}' was accepted",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn orders_conditions_by_rank() {
+        assert!(Condition::Mint > Condition::NearMint);
+        assert!(Condition::VeryGoodMinus > Condition::GoodPlus);
+        assert!(Condition::Poor > Condition::Generic);
+        assert_eq!(
+            Condition::VeryGood.at_least(),
+            vec![
+                Condition::Mint,
+                Condition::NearMint,
+                Condition::NearMintMinus,
+                Condition::VeryGoodPlus,
+                Condition::VeryGood,
+            ]
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use axum::{response::IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::{types::time::OffsetDateTime, Type};
+
+use crate::{constants, store::models::EquipmentJoin, types::deserialize_some};
+
+#[derive(Serialize, Deserialize, Type, Debug, Clone, Copy, PartialEq, Eq)]
+#[sqlx(type_name = "varchar")]
+pub enum EquipmentKind {
+    Turntable,
+    Cartridge,
+    Stylus,
+}
+
+/// A piece of playback equipment. One turntable and one cartridge are active at a time and every cartridge holds at
+/// most one active stylus. Logged plays are attributed to the active equipment, which accumulates their duration.
+#[derive(Serialize)]
+pub struct Equipment {
+    pub id: String,
+    pub kind: EquipmentKind,
+    pub name: String,
+    pub manufacturer: Option<String>,
+    pub model: Option<String>,
+    /// The cartridge a stylus is mounted on.
+    pub cartridge_id: Option<String>,
+    pub active: bool,
+    /// Expected lifetime of a stylus in hours of play.
+    pub lifetime_hours: Option<i32>,
+    pub play_count: i64,
+    /// Hours of play accumulated from the track durations of the played sides.
+    pub hours: f64,
+    /// Set once a stylus approaches or exceeds its lifetime.
+    pub warning: Option<String>,
+    pub notes: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub modified_at: OffsetDateTime,
+}
+
+/// Returns a warning once the equipment called `name` has been played for more than the warning share of its lifetime.
+/// Equipment without a lifetime never wears out.
+pub fn wear_warning(
+    name: &str,
+    lifetime_hours: Option<i32>,
+    played_seconds: i64,
+) -> Option<String> {
+    let lifetime = lifetime_hours?;
+    let lifetime_seconds = i64::from(lifetime) * 3600;
+
+    if played_seconds >= lifetime_seconds {
+        Some(format!(
+            "{} has exceeded its lifetime of {} hours",
+            name, lifetime
+        ))
+    } else if played_seconds * 100 >= lifetime_seconds * constants::STYLUS_WEAR_WARNING_PERCENT {
+        Some(format!(
+            "{} has reached {}% of its lifetime of {} hours",
+            name,
+            played_seconds * 100 / lifetime_seconds,
+            lifetime
+        ))
+    } else {
+        None
+    }
+}
+
+impl From<EquipmentJoin> for Equipment {
+    fn from(ej: EquipmentJoin) -> Self {
+        let seconds = ej.equipment_played_seconds;
+
+        let warning = wear_warning(&ej.equipment_name, ej.equipment_lifetime_hours, seconds);
+
+        Self {
+            id: ej.equipment_id,
+            kind: ej.equipment_kind,
+            name: ej.equipment_name,
+            manufacturer: ej.equipment_manufacturer,
+            model: ej.equipment_model,
+            cartridge_id: ej.equipment_cartridge_id,
+            active: ej.equipment_active,
+            lifetime_hours: ej.equipment_lifetime_hours,
+            play_count: ej.equipment_play_count,
+            hours: (seconds as f64 / 36.0).round() / 100.0,
+            warning,
+            notes: ej.equipment_notes,
+            created_at: ej.equipment_created_at,
+            modified_at: ej.equipment_modified_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct GetEquipmentQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub kind: Option<EquipmentKind>,
+    pub active: Option<bool>,
+}
+
+/// Adds a piece of equipment. Activating it deactivates the equipment of the same kind which was active before, or
+/// for a stylus the stylus which was mounted on the same cartridge.
+#[derive(Deserialize)]
+pub struct CreateEquipmentRequest {
+    pub kind: EquipmentKind,
+    pub name: String,
+    pub manufacturer: Option<String>,
+    pub model: Option<String>,
+    pub cartridge_id: Option<String>,
+    #[serde(default)]
+    pub active: bool,
+    pub lifetime_hours: Option<i32>,
+    #[serde(default)]
+    pub notes: String,
+}
+
+/// A sparse update of a piece of equipment. Omitted fields keep their current value, optional fields are removed by
+/// setting them to `null`. The kind cannot be changed.
+#[derive(Deserialize)]
+pub struct UpdateEquipmentRequest {
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub manufacturer: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub model: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub cartridge_id: Option<Option<String>>,
+    pub active: Option<bool>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub lifetime_hours: Option<Option<i32>>,
+    pub notes: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct GetEquipmentListResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    equipment: Option<Vec<Equipment>>,
+}
+
+impl GetEquipmentListResponse {
+    pub fn success(equipment: Vec<Equipment>, total: i64) -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+            total: Some(total),
+            equipment: Some(equipment),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            total: None,
+            equipment: None,
+        }
+    }
+}
+
+impl IntoResponse for GetEquipmentListResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
+
+/// Response of the endpoints which return a single piece of equipment, i.e. get, create and update.
+#[derive(Serialize)]
+pub struct EquipmentResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    equipment: Option<Equipment>,
+}
+
+impl EquipmentResponse {
+    pub fn success(equipment: Equipment) -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+            equipment: Some(equipment),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            equipment: None,
+        }
+    }
+}
+
+impl IntoResponse for EquipmentResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
+
+#[derive(Serialize)]
+pub struct DeleteEquipmentResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl DeleteEquipmentResponse {
+    pub fn success() -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+        }
+    }
+}
+
+impl IntoResponse for DeleteEquipmentResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    postgres::{PgHasArrayType, PgTypeInfo},
+    Type,
+};
+
+use crate::types::MediaType;
+
+/// Diameter of a record.
+#[derive(Serialize, Deserialize, Type, Clone, Copy, PartialEq, Eq, Debug)]
+#[sqlx(type_name = "varchar")]
+pub enum DiscSize {
+    #[serde(rename = "7\"", alias = "7in")]
+    Inch7,
+    #[serde(rename = "10\"", alias = "10in")]
+    Inch10,
+    #[serde(rename = "12\"", alias = "12in")]
+    Inch12,
+}
+
+/// Playback speed of a record in revolutions per minute.
+#[derive(Serialize, Deserialize, Type, Clone, Copy, PartialEq, Eq, Debug)]
+#[sqlx(type_name = "varchar")]
+pub enum Speed {
+    #[serde(rename = "33⅓", alias = "33", alias = "33 1/3")]
+    Rpm33,
+    #[serde(rename = "45")]
+    Rpm45,
+    #[serde(rename = "78")]
+    Rpm78,
+}
+
+#[derive(Serialize, Deserialize, Type, Clone, Copy, PartialEq, Eq, Debug)]
+#[sqlx(type_name = "varchar")]
+pub enum ReleaseType {
+    #[serde(rename = "LP")]
+    Lp,
+    #[serde(rename = "EP")]
+    Ep,
+    Single,
+}
+
+#[derive(Serialize, Deserialize, Type, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[sqlx(type_name = "varchar")]
+pub enum Packaging {
+    Gatefold,
+    Obi,
+    Insert,
+    Booklet,
+    Poster,
+    Slipcase,
+    Digipak,
+    JewelCase,
+}
+
+// Packaging is stored as a VARCHAR array, which the derive does not cover for enums
+impl PgHasArrayType for Packaging {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_varchar")
+    }
+}
+
+/// Physical format details of a media entry. All details are optional, disc size, speed and weight only apply to
+/// vinyl and box sets.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct MediaFormat {
+    pub size: Option<DiscSize>,
+    pub speed: Option<Speed>,
+    pub release_type: Option<ReleaseType>,
+    /// Weight of a single record in grams, e.g. 180.
+    pub weight: Option<i32>,
+    #[serde(default)]
+    pub packaging: Vec<Packaging>,
+}
+
+impl MediaFormat {
+    /// Validates the format details against the `media_type` and returns them with sorted and deduplicated
+    /// packaging.
+    pub fn normalize(mut self, media_type: &MediaType) -> Result<Self, String> {
+        let is_record = matches!(media_type, MediaType::Vinyl | MediaType::BoxSet);
+
+        if !is_record && self.size.is_some() {
+            return Err(String::from("Only vinyl and box sets have a disc size"));
+        }
+
+        if !is_record && self.speed.is_some() {
+            return Err(String::from("Only vinyl and box sets have a speed"));
+        }
+
+        match self.weight {
+            Some(_) if !is_record => {
+                return Err(String::from("Only vinyl and box sets have a weight"))
+            }
+            Some(weight) if weight < 1 => {
+                return Err(format!("Invalid weight {}, expected grams", weight))
+            }
+            _ => {}
+        }
+
+        self.packaging.sort();
+        self.packaging.dedup();
+
+        Ok(self)
+    }
+}
@@ -0,0 +1,128 @@
+use axum::{response::IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::types::time::OffsetDateTime;
+
+use crate::{
+    store::models::GradingsJoin,
+    types::{Condition, Grade, GradingScale},
+};
+
+/// A single grading of the media and sleeve condition of a media entry. The latest grading is the current condition
+/// of the entry.
+#[derive(Serialize, Clone)]
+pub struct ConditionGrading {
+    pub id: i32,
+    #[serde(with = "time::serde::rfc3339")]
+    pub graded_at: OffsetDateTime,
+    pub grader: Option<String>,
+    pub media_condition: Grade,
+    pub sleeve_condition: Grade,
+    pub notes: String,
+}
+
+impl ConditionGrading {
+    /// Presents the conditions of the grading in the grading `scale`.
+    pub fn set_scale(&mut self, scale: GradingScale) {
+        self.media_condition.scale = scale;
+        self.sleeve_condition.scale = scale;
+    }
+}
+
+impl From<GradingsJoin> for ConditionGrading {
+    fn from(gj: GradingsJoin) -> Self {
+        Self {
+            id: gj.grading_id,
+            graded_at: gj.grading_graded_at,
+            grader: gj.grading_grader,
+            media_condition: Grade::from(gj.grading_media_condition),
+            sleeve_condition: Grade::from(gj.grading_sleeve_condition),
+            notes: gj.grading_notes,
+        }
+    }
+}
+
+/// A new grading. If `graded_at` is omitted, the current time is used. Gradings in the past are added to the
+/// history and only change the current condition if they are the latest grading.
+#[derive(Deserialize)]
+pub struct CreateGradingRequest {
+    pub media_condition: Condition,
+    pub sleeve_condition: Condition,
+    pub grader: Option<String>,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub graded_at: Option<OffsetDateTime>,
+}
+
+#[derive(Serialize)]
+pub struct GetGradingsResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gradings: Option<Vec<ConditionGrading>>,
+}
+
+impl GetGradingsResponse {
+    pub fn success(gradings: Vec<ConditionGrading>) -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+            gradings: Some(gradings),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            gradings: None,
+        }
+    }
+}
+
+impl IntoResponse for GetGradingsResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
+
+#[derive(Serialize)]
+pub struct GradingResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    grading: Option<ConditionGrading>,
+}
+
+impl GradingResponse {
+    pub fn success(grading: ConditionGrading) -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+            grading: Some(grading),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            grading: None,
+        }
+    }
+}
+
+impl IntoResponse for GradingResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
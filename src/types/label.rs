@@ -1,20 +1,280 @@
-use serde::Serialize;
+use axum::{response::IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
 
-#[derive(Serialize)]
+use crate::types::{Link, LinkRequest};
+
+#[derive(Serialize, Default, Clone)]
 pub struct Label {
     pub id: String,
     pub name: String,
     pub label_code: String,
-    pub urls: String,
+    pub links: Vec<Link>,
+}
+
+impl Label {
+    /// Validates and normalizes a label code to the `LC-xxxxx` format. The prefix is matched case-insensitively, the
+    /// separator may be a dash, a space or omitted and the number is zero-padded to five digits, e.g. `lc 162`
+    /// becomes `LC-00162`. An empty label code is valid, as not every label has one.
+    pub fn normalize_label_code(label_code: &str) -> Result<String, String> {
+        let label_code = label_code.trim();
+        if label_code.is_empty() {
+            return Ok(String::new());
+        }
+
+        let invalid = || {
+            format!(
+                "Invalid label code '{}', expected format LC-xxxxx",
+                label_code
+            )
+        };
+
+        let number = match label_code.get(..2) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("LC") => {
+                label_code[2..].trim_start_matches(['-', ' '])
+            }
+            _ => return Err(invalid()),
+        };
+
+        if number.is_empty() || number.len() > 5 || !number.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+
+        Ok(format!("LC-{:0>5}", number))
+    }
+}
+
+/// A label referenced in a media payload. Either points to an already existing label by its ID or describes a new
+/// label which gets created alongside the media entry.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum LabelRef {
+    Existing {
+        id: String,
+    },
+    New {
+        name: String,
+        #[serde(default)]
+        label_code: String,
+        #[serde(default)]
+        links: Vec<LinkRequest>,
+    },
+}
+
+/// A label a media entry was released on, together with the catalogue number the label assigned to it.
+#[derive(Serialize, Clone)]
+pub struct MediaLabel {
+    #[serde(flatten)]
+    pub label: Label,
+    pub catalogue: String,
+}
+
+/// A label referenced in a media payload together with the catalogue number of the media entry on that label.
+#[derive(Deserialize)]
+pub struct MediaLabelRef {
+    #[serde(flatten)]
+    pub label: LabelRef,
+    #[serde(default)]
+    pub catalogue: String,
+}
+
+/// Changes to the labels of a media entry. `set` replaces all labels, otherwise labels can be added and removed one
+/// at a time via `add` and `remove`. Adding an already linked label updates its catalogue number.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct LabelsPatch {
+    pub set: Option<Vec<MediaLabelRef>>,
+    pub add: Vec<MediaLabelRef>,
+    pub remove: Vec<String>,
+}
+
+impl LabelsPatch {
+    pub fn is_empty(&self) -> bool {
+        self.set.is_none() && self.add.is_empty() && self.remove.is_empty()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct GetLabelsQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateLabelRequest {
+    pub name: String,
+    #[serde(default)]
+    pub label_code: String,
+    #[serde(default)]
+    pub links: Vec<LinkRequest>,
+}
+
+/// A sparse update of a label. Omitted fields keep their current value.
+#[derive(Deserialize)]
+pub struct UpdateLabelRequest {
+    pub name: Option<String>,
+    pub label_code: Option<String>,
+    /// Replaces all links of the label.
+    pub links: Option<Vec<LinkRequest>>,
+}
+
+#[derive(Serialize)]
+pub struct GetLabelsResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    labels: Option<Vec<Label>>,
+}
+
+impl GetLabelsResponse {
+    pub fn success(labels: Vec<Label>, total: i64) -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+            total: Some(total),
+            labels: Some(labels),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            total: None,
+            labels: None,
+        }
+    }
+}
+
+impl IntoResponse for GetLabelsResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
 }
 
-impl Default for Label {
-    fn default() -> Self {
+/// Response of the endpoints which return a single label, i.e. get, create and update.
+#[derive(Serialize)]
+pub struct LabelResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label: Option<Label>,
+}
+
+impl LabelResponse {
+    pub fn success(label: Label) -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+            label: Some(label),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
         Self {
-            id: Default::default(),
-            name: Default::default(),
-            label_code: Default::default(),
-            urls: Default::default(),
+            status: String::from("error"),
+            error: Some(message.into()),
+            label: None,
+        }
+    }
+}
+
+impl IntoResponse for LabelResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
+
+#[derive(Serialize)]
+pub struct DeleteLabelResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl DeleteLabelResponse {
+    pub fn success() -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+        }
+    }
+}
+
+impl IntoResponse for DeleteLabelResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_label_codes() {
+        for (input, expected) in [
+            ("LC-00162", "LC-00162"),
+            ("LC-162", "LC-00162"),
+            ("lc 162", "LC-00162"),
+            ("Lc162", "LC-00162"),
+            (" LC-12345 ", "LC-12345"),
+            ("LC 0", "LC-00000"),
+        ] {
+            assert_eq!(
+                Label::normalize_label_code(input),
+                Ok(String::from(expected)),
+                "'{}'",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn accepts_empty_label_codes() {
+        assert_eq!(Label::normalize_label_code(""), Ok(String::new()));
+        assert_eq!(Label::normalize_label_code("   "), Ok(String::new()));
+    }
+
+    #[test]
+    fn rejects_invalid_label_codes() {
+        for input in [
+            "162",
+            "L",
+            "LC",
+            "LC-",
+            "LC-123456",
+            "LC-12a",
+            "LC-1.5",
+            "LX-00162",
+            "LC_00162",
+            "LC-ü",
+        ] {
+            assert!(
+                Label::normalize_label_code(input).is_err(),
+                "'{}' was accepted",
+                input
+            );
         }
     }
 }
@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+use sqlx::Type;
+use url::Url;
+
+/// The kind of an external link. Clients use it to render icons and deep links.
+#[derive(Serialize, Deserialize, Type, Clone, Copy, PartialEq, Eq, Debug)]
+#[sqlx(type_name = "varchar")]
+pub enum LinkKind {
+    Discogs,
+    MusicBrainz,
+    Bandcamp,
+    Spotify,
+    Official,
+    Other,
+}
+
+impl LinkKind {
+    /// Guesses the kind of a link from its host. Links to unknown hosts are of kind `Other`.
+    fn from_host(host: &str) -> Self {
+        let host = host.trim_start_matches("www.");
+
+        match host {
+            "discogs.com" => Self::Discogs,
+            "musicbrainz.org" => Self::MusicBrainz,
+            "open.spotify.com" | "spotify.com" => Self::Spotify,
+            _ if host == "bandcamp.com" || host.ends_with(".bandcamp.com") => Self::Bandcamp,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// The kind of entity a link belongs to.
+#[derive(Type, Clone, Copy)]
+#[sqlx(type_name = "varchar")]
+pub enum LinkOwner {
+    Release,
+    Artist,
+    Label,
+    Track,
+    Shop,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Link {
+    pub kind: LinkKind,
+    pub url: String,
+}
+
+/// A link in a request payload. If `kind` is omitted, it is derived from the host of the URL.
+#[derive(Deserialize)]
+pub struct LinkRequest {
+    pub url: String,
+    pub kind: Option<LinkKind>,
+}
+
+impl Link {
+    /// Validates and normalizes the links in `links`. Only absolute HTTP(S) URLs are accepted. URLs are normalized,
+    /// e.g. the host is lowercased, and duplicates are dropped, keeping the first occurrence.
+    pub fn normalize_links(links: Vec<LinkRequest>) -> Result<Vec<Link>, String> {
+        let mut normalized: Vec<Link> = Vec::new();
+
+        for link in links {
+            let url = match Url::parse(link.url.trim()) {
+                Ok(url) => url,
+                Err(_) => return Err(format!("Invalid URL '{}'", link.url)),
+            };
+
+            let host = match url.host_str() {
+                Some(host) if matches!(url.scheme(), "http" | "https") => host.to_string(),
+                _ => {
+                    return Err(format!(
+                        "Invalid URL '{}', expected an absolute HTTP(S) URL",
+                        link.url
+                    ))
+                }
+            };
+
+            let url = url.to_string();
+            if normalized.iter().any(|l| l.url == url) {
+                continue;
+            }
+
+            normalized.push(Link {
+                kind: link.kind.unwrap_or_else(|| LinkKind::from_host(&host)),
+                url,
+            });
+        }
+
+        Ok(normalized)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(url: &str) -> LinkRequest {
+        LinkRequest {
+            url: url.to_string(),
+            kind: None,
+        }
+    }
+
+    fn normalize(urls: &[&str]) -> Result<Vec<(LinkKind, String)>, String> {
+        let links = Link::normalize_links(urls.iter().map(|url| request(url)).collect())?;
+        Ok(links.into_iter().map(|l| (l.kind, l.url)).collect())
+    }
+
+    #[test]
+    fn accepts_http_and_https_urls() {
+        assert_eq!(
+            normalize(&["http://example.com/a", " https://example.com/b "]),
+            Ok(vec![
+                (LinkKind::Other, String::from("http://example.com/a")),
+                (LinkKind::Other, String::from("https://example.com/b")),
+            ])
+        );
+    }
+
+    #[test]
+    fn rejects_other_schemes_and_relative_urls() {
+        for url in [
+            "",
+            "example.com",
+            "/release/1",
+            "ftp://example.com/a",
+            "mailto:label@example.com",
+            "javascript:alert(1)",
+            "file:///etc/passwd",
+            "https://",
+        ] {
+            assert!(normalize(&[url]).is_err(), "'{}' was accepted", url);
+        }
+    }
+
+    #[test]
+    fn normalizes_and_deduplicates_urls() {
+        assert_eq!(
+            normalize(&[
+                "https://Example.COM",
+                "https://example.com/",
+                "https://example.com/a",
+                "https://EXAMPLE.com/a",
+            ]),
+            Ok(vec![
+                (LinkKind::Other, String::from("https://example.com/")),
+                (LinkKind::Other, String::from("https://example.com/a")),
+            ])
+        );
+    }
+
+    #[test]
+    fn detects_link_kinds_from_the_host() {
+        for (url, kind) in [
+            ("https://www.discogs.com/release/1", LinkKind::Discogs),
+            ("https://discogs.com/release/1", LinkKind::Discogs),
+            ("https://musicbrainz.org/release/x", LinkKind::MusicBrainz),
+            ("https://open.spotify.com/album/x", LinkKind::Spotify),
+            ("https://artist.bandcamp.com/album/x", LinkKind::Bandcamp),
+            ("https://bandcamp.com/artist", LinkKind::Bandcamp),
+            ("https://notbandcamp.com/album/x", LinkKind::Other),
+            ("https://discogs.com.example.org/", LinkKind::Other),
+            ("https://example.com/", LinkKind::Other),
+        ] {
+            assert_eq!(normalize(&[url]).unwrap()[0].0, kind, "'{}'", url);
+        }
+    }
+
+    #[test]
+    fn keeps_explicit_link_kinds() {
+        let links = Link::normalize_links(vec![LinkRequest {
+            url: String::from("https://www.discogs.com/label/1"),
+            kind: Some(LinkKind::Official),
+        }])
+        .unwrap();
+        assert_eq!(links[0].kind, LinkKind::Official);
+    }
+}
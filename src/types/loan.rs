@@ -0,0 +1,192 @@
+use axum::{response::IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::types::time::{Date, OffsetDateTime};
+
+use crate::{store::models::LoansJoin, types::deserialize_some};
+
+/// A media entry lent to someone. Loans without a return date are open, a media entry has at most one open loan.
+#[derive(Serialize, Clone)]
+pub struct Loan {
+    pub id: String,
+    pub media_id: String,
+    /// Title of the lent media entry.
+    pub title: String,
+    pub borrower: String,
+    /// How to reach the borrower, e.g. a phone number or an email address.
+    pub contact: Option<String>,
+    pub loan_date: Date,
+    pub due_date: Option<Date>,
+    pub return_date: Option<Date>,
+    /// Whether the loan is still open past its due date.
+    pub overdue: bool,
+    pub notes: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub modified_at: OffsetDateTime,
+}
+
+impl From<LoansJoin> for Loan {
+    fn from(lj: LoansJoin) -> Self {
+        let today = OffsetDateTime::now_utc().date();
+
+        Self {
+            overdue: lj.loan_return_date.is_none() && lj.loan_due_date.is_some_and(|d| d < today),
+            id: lj.loan_id,
+            media_id: lj.media_id,
+            title: lj.media_title,
+            borrower: lj.loan_borrower,
+            contact: lj.loan_contact,
+            loan_date: lj.loan_loan_date,
+            due_date: lj.loan_due_date,
+            return_date: lj.loan_return_date,
+            notes: lj.loan_notes,
+            created_at: lj.loan_created_at,
+            modified_at: lj.loan_modified_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct GetLoansQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    /// Only list open loans if `true`, only returned loans if `false`.
+    pub open: Option<bool>,
+}
+
+/// Lends a media entry to a borrower. If `loan_date` is omitted, the current date is used.
+#[derive(Deserialize)]
+pub struct CreateLoanRequest {
+    pub borrower: String,
+    pub contact: Option<String>,
+    pub loan_date: Option<Date>,
+    pub due_date: Option<Date>,
+    #[serde(default)]
+    pub notes: String,
+}
+
+/// A sparse update of a loan. Omitted fields keep their current value. Setting `return_date` marks the loan as
+/// returned, the contact, due date and return date are removed by setting them to `null`.
+#[derive(Deserialize)]
+pub struct UpdateLoanRequest {
+    pub borrower: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub contact: Option<Option<String>>,
+    pub loan_date: Option<Date>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub due_date: Option<Option<Date>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub return_date: Option<Option<Date>>,
+    pub notes: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct GetLoansResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    loans: Option<Vec<Loan>>,
+}
+
+impl GetLoansResponse {
+    pub fn success(loans: Vec<Loan>, total: i64) -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+            total: Some(total),
+            loans: Some(loans),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            total: None,
+            loans: None,
+        }
+    }
+}
+
+impl IntoResponse for GetLoansResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
+
+/// Response of the endpoints which return a single loan, i.e. get, create and update.
+#[derive(Serialize)]
+pub struct LoanResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    loan: Option<Loan>,
+}
+
+impl LoanResponse {
+    pub fn success(loan: Loan) -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+            loan: Some(loan),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            loan: None,
+        }
+    }
+}
+
+impl IntoResponse for LoanResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
+
+#[derive(Serialize)]
+pub struct DeleteLoanResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl DeleteLoanResponse {
+    pub fn success() -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+        }
+    }
+}
+
+impl IntoResponse for DeleteLoanResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
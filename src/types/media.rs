@@ -1,55 +1,160 @@
+use std::{fmt, str::FromStr};
+
 use axum::{response::IntoResponse, Json};
-use serde::Serialize;
+use serde::{de, Deserialize, Deserializer, Serialize};
 use serde_json::json;
-use sqlx::{types::time::PrimitiveDateTime, Type};
+use sqlx::{
+    types::time::{Date, OffsetDateTime},
+    Type,
+};
 
 use crate::{
     store::models::MediaJoin,
-    types::{Artist, BuyCondition, Condition, Label, Track},
+    types::{
+        Amount, Artist, ArtistRef, ArtistsPatch, BuyCondition, Condition, ConditionGrading,
+        CreateTrackRequest, DatePrecision, DiscSize, Grade, GradingScale, LabelsPatch, Link,
+        LinkRequest, Loan, MediaFormat, MediaLabel, MediaLabelRef, Packaging, Pressing, Price,
+        Purchase, PurchaseRequest, ReleaseDate, ReleaseType, Speed, Track, TrackDuration,
+        TracksPatch,
+    },
 };
 
 #[derive(Serialize)]
 pub struct Media {
     pub id: String,
+    /// The release this media entry is a copy of. Title, format, artists, labels, tracks and links are shared by all
+    /// copies of a release.
+    pub release_id: String,
     pub title: String,
     pub media_type: MediaType,
+    pub format: MediaFormat,
+    pub pressing: Pressing,
     pub artists: Vec<Artist>,
-    pub label: Label,
-    pub catalogue: String,
+    pub labels: Vec<MediaLabel>,
     pub tracks: Vec<Track>,
-    pub release_date: String,
-    pub purchase_date: String,
-    pub media_condition: Condition,
-    pub sleeve_condition: Condition,
+    /// Total runtime of all tracks.
+    pub runtime: TrackDuration,
+    pub side_runtimes: Vec<SideRuntime>,
+    pub release_date: ReleaseDate,
+    pub purchase_date: Date,
+    pub purchase: Purchase,
+    pub media_condition: Grade,
+    pub sleeve_condition: Grade,
+    /// The grading the media and sleeve condition stem from. The full history is available separately.
+    pub latest_grading: Option<ConditionGrading>,
     pub bought: BuyCondition,
-    pub created_at: String,
-    pub modified_at: String,
+    /// The open loan if the media entry is currently lent out. The loan history is available separately.
+    pub loan: Option<Loan>,
+    /// How often the media entry was played according to the listening log.
+    pub play_count: i64,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_played: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub modified_at: OffsetDateTime,
     pub notes: String,
+    pub links: Vec<Link>,
+}
+
+/// Total runtime of the tracks on one side of a media entry, or on one disc for media without sides.
+#[derive(Serialize, Clone)]
+pub struct SideRuntime {
+    pub disc: i32,
+    pub side: Option<String>,
+    pub runtime: TrackDuration,
+}
+
+impl Media {
+    /// Sets the tracks of the media entry and computes its total and per-side runtimes. `tracks` have to be ordered
+    /// by disc, side and index.
+    pub fn set_tracks(&mut self, tracks: Vec<Track>) {
+        let mut side_runtimes: Vec<SideRuntime> = Vec::new();
+        for track in &tracks {
+            match side_runtimes.last_mut() {
+                Some(last)
+                    if last.disc == track.position.disc && last.side == track.position.side =>
+                {
+                    last.runtime.0 += track.duration.0
+                }
+                _ => side_runtimes.push(SideRuntime {
+                    disc: track.position.disc,
+                    side: track.position.side.clone(),
+                    runtime: track.duration,
+                }),
+            }
+        }
+
+        self.runtime = TrackDuration(side_runtimes.iter().map(|s| s.runtime.0).sum());
+        self.side_runtimes = side_runtimes;
+        self.tracks = tracks;
+    }
+
+    /// Presents the conditions of the media entry in the grading `scale`.
+    pub fn set_scale(&mut self, scale: GradingScale) {
+        self.media_condition.scale = scale;
+        self.sleeve_condition.scale = scale;
+        if let Some(grading) = &mut self.latest_grading {
+            grading.set_scale(scale);
+        }
+    }
 }
 
 impl From<MediaJoin> for Media {
     fn from(mj: MediaJoin) -> Self {
         Self {
             id: mj.media_id,
+            release_id: mj.media_release_id,
             title: mj.media_title,
             media_type: mj.media_media_type,
-            artists: Vec::new(),
-            label: Label {
-                id: mj.label_id,
-                name: mj.label_name,
-                label_code: mj.label_label_code,
-                urls: mj.label_urls,
+            format: MediaFormat {
+                size: mj.media_disc_size,
+                speed: mj.media_speed,
+                release_type: mj.media_release_type,
+                weight: mj.media_weight,
+                packaging: mj.media_packaging,
             },
-            catalogue: mj.media_catalogue,
+            pressing: Pressing {
+                plant: mj.media_pressing_plant,
+                country: mj.media_country,
+                variant: mj.media_variant,
+                edition_number: mj.media_edition_number,
+                edition_size: mj.media_edition_size,
+                matrices: Vec::new(),
+            },
+            artists: Vec::new(),
+            labels: Vec::new(),
             tracks: Vec::new(),
-            release_date: mj.media_release_date.to_string(),
-            purchase_date: mj.media_purchase_date.to_string(),
-            media_condition: mj.media_media_condition,
-            sleeve_condition: mj.media_sleeve_condition,
+            runtime: TrackDuration::default(),
+            side_runtimes: Vec::new(),
+            release_date: ReleaseDate {
+                date: mj.media_release_date,
+                precision: mj.media_release_date_precision,
+            },
+            purchase_date: mj.media_purchase_date,
+            purchase: Purchase {
+                price: mj
+                    .media_price
+                    .zip(mj.media_currency)
+                    .map(|(amount, currency)| Price {
+                        amount: Amount::new(amount, &currency),
+                        currency,
+                    }),
+                shop: None,
+                seller: mj.media_seller,
+                order_reference: mj.media_order_reference,
+            },
+            media_condition: Grade::from(mj.media_media_condition),
+            sleeve_condition: Grade::from(mj.media_sleeve_condition),
+            latest_grading: None,
             bought: mj.media_bought,
-            created_at: mj.media_created_at.to_string(),
-            modified_at: mj.media_modified_at.to_string(),
+            loan: None,
+            play_count: 0,
+            last_played: None,
+            created_at: mj.media_created_at,
+            modified_at: mj.media_modified_at,
             notes: mj.media_notes,
+            links: Vec::new(),
         }
     }
 }
@@ -57,20 +162,33 @@ impl From<RawMedia> for Media {
     fn from(rm: RawMedia) -> Self {
         Self {
             id: rm.id,
+            release_id: rm.release_id,
             title: rm.title,
             media_type: rm.media_type,
+            format: MediaFormat::default(),
+            pressing: Pressing::default(),
             artists: Vec::new(),
-            label: Label::default(),
-            catalogue: rm.catalogue,
+            labels: Vec::new(),
             tracks: Vec::new(),
-            release_date: rm.release_date.to_string(),
-            purchase_date: rm.purchase_date.to_string(),
-            media_condition: rm.media_condition,
-            sleeve_condition: rm.sleeve_condition,
+            runtime: TrackDuration::default(),
+            side_runtimes: Vec::new(),
+            release_date: ReleaseDate {
+                date: rm.release_date,
+                precision: rm.release_date_precision,
+            },
+            purchase_date: rm.purchase_date,
+            purchase: Purchase::default(),
+            media_condition: Grade::from(rm.media_condition),
+            sleeve_condition: Grade::from(rm.sleeve_condition),
+            latest_grading: None,
             bought: rm.bought,
-            created_at: rm.created_at.to_string(),
-            modified_at: rm.modified_at.to_string(),
+            loan: None,
+            play_count: 0,
+            last_played: None,
+            created_at: rm.created_at,
+            modified_at: rm.modified_at,
             notes: rm.notes,
+            links: Vec::new(),
         }
     }
 }
@@ -78,48 +196,270 @@ impl From<RawMedia> for Media {
 #[derive(Type)]
 pub struct RawMedia {
     pub id: String,
+    pub release_id: String,
     pub title: String,
     pub media_type: MediaType,
-    pub catalogue: String,
-    pub release_date: PrimitiveDateTime,
-    pub purchase_date: PrimitiveDateTime,
+    pub release_date: Date,
+    pub release_date_precision: DatePrecision,
+    pub purchase_date: Date,
     pub media_condition: Condition,
     pub sleeve_condition: Condition,
     pub bought: BuyCondition,
-    pub created_at: PrimitiveDateTime,
-    pub modified_at: PrimitiveDateTime,
+    pub created_at: OffsetDateTime,
+    pub modified_at: OffsetDateTime,
     pub notes: String,
 }
 
-#[derive(Serialize, Type, Clone)]
+/// Kind of a media entry. Accepted case-insensitively, `8-track`, `reel-to-reel` and `box set` are accepted as well.
+#[derive(Serialize, Type, Clone, Debug)]
+#[sqlx(type_name = "varchar")]
 pub enum MediaType {
     Vinyl,
     Tape,
     Cd,
+    MiniDisc,
+    EightTrack,
+    ReelToReel,
+    BoxSet,
 }
 
-impl From<String> for MediaType {
-    fn from(input: String) -> Self {
-        match input.to_lowercase().as_str() {
-            "vinyl" => Self::Vinyl,
-            "tape" => Self::Tape,
-            "cd" => Self::Cd,
-            _ => Self::Vinyl,
+impl FromStr for MediaType {
+    type Err = String;
+
+    /// Parses a media type case-insensitively. Unknown media types are rejected.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "vinyl" => Ok(Self::Vinyl),
+            "tape" => Ok(Self::Tape),
+            "cd" => Ok(Self::Cd),
+            "minidisc" => Ok(Self::MiniDisc),
+            "eighttrack" | "8-track" => Ok(Self::EightTrack),
+            "reeltoreel" | "reel-to-reel" => Ok(Self::ReelToReel),
+            "boxset" | "box set" => Ok(Self::BoxSet),
+            _ => Err(format!("Unknown media type '{}'", s)),
         }
     }
 }
 
+impl<'de> Deserialize<'de> for MediaType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CreateMediaRequest {
+    pub title: String,
+    pub media_type: MediaType,
+    #[serde(default)]
+    pub format: MediaFormat,
+    #[serde(default)]
+    pub pressing: Pressing,
+    pub artists: Vec<ArtistRef>,
+    #[serde(default)]
+    pub labels: Vec<MediaLabelRef>,
+    #[serde(default)]
+    pub tracks: Vec<CreateTrackRequest>,
+    pub release_date: ReleaseDate,
+    pub purchase_date: Date,
+    #[serde(default)]
+    pub purchase: PurchaseRequest,
+    pub media_condition: Condition,
+    pub sleeve_condition: Condition,
+    pub bought: BuyCondition,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub links: Vec<LinkRequest>,
+}
+
+/// A sparse update of a media entry. Omitted fields keep their current value. Title, media type, format, artists,
+/// labels, tracks, release date and links belong to the release and change for all of its copies.
+#[derive(Deserialize)]
+pub struct UpdateMediaRequest {
+    /// Moves the media entry to another release, e.g. to merge duplicate releases. A release without copies left is
+    /// deleted. Release fields of the request are applied to the new release.
+    pub release_id: Option<String>,
+    pub title: Option<String>,
+    pub media_type: Option<MediaType>,
+    /// Replaces all format details of the media entry.
+    pub format: Option<MediaFormat>,
+    /// Replaces all pressing details of the media entry, including the matrices.
+    pub pressing: Option<Pressing>,
+    #[serde(default)]
+    pub artists: ArtistsPatch,
+    #[serde(default)]
+    pub labels: LabelsPatch,
+    #[serde(default)]
+    pub tracks: TracksPatch,
+    pub release_date: Option<ReleaseDate>,
+    pub purchase_date: Option<Date>,
+    /// Replaces all purchase details of the media entry.
+    pub purchase: Option<PurchaseRequest>,
+    pub media_condition: Option<Condition>,
+    pub sleeve_condition: Option<Condition>,
+    pub bought: Option<BuyCondition>,
+    pub notes: Option<String>,
+    /// Replaces all links of the media entry.
+    pub links: Option<Vec<LinkRequest>>,
+}
+
+#[derive(Deserialize)]
+pub struct DeleteMediaQuery {
+    /// Also delete artists and labels of the media entry which are not linked to any other media entry afterwards.
+    #[serde(default)]
+    pub remove_orphans: bool,
+}
+
+#[derive(Deserialize)]
+pub struct GetMediaEntriesQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub cursor: Option<String>,
+    pub sort: Option<MediaSort>,
+    #[serde(default)]
+    pub order: SortOrder,
+    /// Grading scale the conditions of the entries are returned in.
+    #[serde(default)]
+    pub scale: GradingScale,
+}
+
+/// Filters applied to the media listing. All filters are optional and combined with AND. Date ranges are inclusive.
+#[derive(Deserialize, Default)]
+pub struct MediaFilter {
+    pub media_type: Option<MediaType>,
+    pub size: Option<DiscSize>,
+    pub speed: Option<Speed>,
+    pub release_type: Option<ReleaseType>,
+    pub packaging: Option<Packaging>,
+    pub media_condition: Option<Condition>,
+    pub min_media_condition: Option<Condition>,
+    pub sleeve_condition: Option<Condition>,
+    pub min_sleeve_condition: Option<Condition>,
+    pub bought: Option<BuyCondition>,
+    pub label: Option<String>,
+    pub artist: Option<String>,
+    pub shop: Option<String>,
+    pub release: Option<String>,
+    /// Only list entries which are currently lent out if `true`, only entries at home if `false`.
+    pub lent: Option<bool>,
+    pub release_date_from: Option<Date>,
+    pub release_date_to: Option<Date>,
+    pub purchase_date_from: Option<Date>,
+    pub purchase_date_to: Option<Date>,
+}
+
+/// Keys the media listing can be sorted by.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaSort {
+    Title,
+    Artist,
+    Label,
+    Catalogue,
+    ReleaseDate,
+    PurchaseDate,
+    #[default]
+    CreatedAt,
+    Condition,
+    /// Least recently played first in ascending order, entries which were never played come first.
+    LastPlayed,
+    PlayCount,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Position in the media listing used for keyset pagination. The cursor points to the last entry of a page and stores
+/// the sort key it was created for, the value of that sort key as text and the ID of the entry. It is serialized as
+/// URL-safe base64 encoded JSON.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct MediaCursor {
+    pub sort: MediaSort,
+    pub order: SortOrder,
+    pub value: String,
+    pub id: String,
+}
+
+impl MediaCursor {
+    /// Returns whether the cursor was created for a listing sorted by `sort` in `order`.
+    pub fn matches(&self, sort: MediaSort, order: SortOrder) -> bool {
+        self.sort == sort && self.order == order
+    }
+}
+
+impl fmt::Display for MediaCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let json = json!(self).to_string();
+        write!(
+            f,
+            "{}",
+            base64::encode_config(json, base64::URL_SAFE_NO_PAD)
+        )
+    }
+}
+
+impl FromStr for MediaCursor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match base64::decode_config(s, base64::URL_SAFE_NO_PAD)
+            .ok()
+            .and_then(|json| serde_json::from_slice(&json).ok())
+        {
+            Some(cursor) => Ok(cursor),
+            None => Err(String::from("Invalid cursor")),
+        }
+    }
+}
+
+pub struct MediaEntriesPage {
+    pub entries: Vec<Media>,
+    pub total: i64,
+    pub next_cursor: Option<String>,
+}
+
 #[derive(Serialize)]
 pub struct GetMediaEntriesResponse {
     status: String,
-    entries: Vec<Media>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_cursor: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entries: Option<Vec<Media>>,
 }
 
 impl GetMediaEntriesResponse {
-    pub fn success(entries: Vec<Media>) -> Self {
+    pub fn success(page: MediaEntriesPage) -> Self {
         Self {
             status: String::from("success"),
-            entries,
+            error: None,
+            total: Some(page.total),
+            next_cursor: page.next_cursor,
+            entries: Some(page.entries),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            total: None,
+            next_cursor: None,
+            entries: None,
         }
     }
 }
@@ -166,3 +506,171 @@ impl IntoResponse for GetMediaEntryResponse {
         body.into_response()
     }
 }
+
+#[derive(Serialize)]
+pub struct CreateMediaEntryResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entry: Option<Media>,
+}
+
+impl CreateMediaEntryResponse {
+    pub fn success(entry: Media) -> Self {
+        Self {
+            status: String::from("success"),
+            entry: Some(entry),
+            error: None,
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            entry: None,
+        }
+    }
+}
+
+impl IntoResponse for CreateMediaEntryResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
+
+#[derive(Serialize)]
+pub struct UpdateMediaEntryResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entry: Option<Media>,
+}
+
+impl UpdateMediaEntryResponse {
+    pub fn success(entry: Media) -> Self {
+        Self {
+            status: String::from("success"),
+            entry: Some(entry),
+            error: None,
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            entry: None,
+        }
+    }
+}
+
+impl IntoResponse for UpdateMediaEntryResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
+
+#[derive(Serialize)]
+pub struct DeleteMediaEntryResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl DeleteMediaEntryResponse {
+    pub fn success() -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+        }
+    }
+}
+
+impl IntoResponse for DeleteMediaEntryResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cursor(sort: MediaSort, order: SortOrder) -> MediaCursor {
+        MediaCursor {
+            sort,
+            order,
+            value: String::from("Kind of Blue"),
+            id: String::from("V1StGXR8_Z5jdHi6B-myT"),
+        }
+    }
+
+    #[test]
+    fn round_trips_cursors() {
+        for (sort, order) in [
+            (MediaSort::CreatedAt, SortOrder::Asc),
+            (MediaSort::Title, SortOrder::Desc),
+            (MediaSort::Catalogue, SortOrder::Asc),
+            (MediaSort::LastPlayed, SortOrder::Desc),
+        ] {
+            let encoded = cursor(sort, order).to_string();
+            assert_eq!(encoded.parse(), Ok(cursor(sort, order)));
+        }
+    }
+
+    #[test]
+    fn encodes_cursors_url_safe() {
+        let mut cursor = cursor(MediaSort::Title, SortOrder::Asc);
+        cursor.value = String::from("??>>~~ü");
+        let encoded = cursor.to_string();
+        assert!(encoded
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_eq!(encoded.parse(), Ok(cursor));
+    }
+
+    #[test]
+    fn rejects_malformed_cursors() {
+        let encode = |json: &str| base64::encode_config(json, base64::URL_SAFE_NO_PAD);
+        for input in [
+            String::new(),
+            String::from("not a cursor!"),
+            encode("not json"),
+            encode(r#"{"sort":"title","order":"asc","value":"A"}"#),
+            encode(r#"{"sort":"colour","order":"asc","value":"A","id":"x"}"#),
+            encode(r#"{"sort":"title","order":"up","value":"A","id":"x"}"#),
+        ] {
+            assert!(
+                input.parse::<MediaCursor>().is_err(),
+                "'{}' was accepted",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn matches_only_the_sort_order_it_was_created_for() {
+        let cursor = cursor(MediaSort::Title, SortOrder::Asc);
+        assert!(cursor.matches(MediaSort::Title, SortOrder::Asc));
+        assert!(!cursor.matches(MediaSort::Title, SortOrder::Desc));
+        assert!(!cursor.matches(MediaSort::Catalogue, SortOrder::Asc));
+    }
+}
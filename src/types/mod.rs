@@ -1,11 +1,39 @@
 mod artist;
 mod condition;
+mod equipment;
+mod format;
+mod grading;
 mod label;
+mod link;
+mod loan;
 mod media;
+mod play;
+mod pressing;
+mod purchase;
+mod release;
+mod release_date;
+mod search;
+mod shop;
+mod stats;
 mod track;
+mod want;
 
 pub use artist::*;
 pub use condition::*;
+pub use equipment::*;
+pub use format::*;
+pub use grading::*;
 pub use label::*;
+pub use link::*;
+pub use loan::*;
 pub use media::*;
+pub use play::*;
+pub use pressing::*;
+pub use purchase::*;
+pub use release::*;
+pub use release_date::*;
+pub use search::*;
+pub use shop::*;
+pub use stats::*;
 pub use track::*;
+pub use want::*;
@@ -0,0 +1,179 @@
+use axum::{response::IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::types::time::OffsetDateTime;
+
+use crate::{store::models::PlaysJoin, types::TrackDuration};
+
+/// A single play of a media entry logged in the listening log.
+#[derive(Serialize)]
+pub struct Play {
+    pub id: String,
+    pub media_id: String,
+    /// Title of the played media entry.
+    pub title: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub played_at: OffsetDateTime,
+    /// The sides which were played. Empty if the whole media entry was played.
+    pub sides: Vec<String>,
+    /// Runtime of the played record sides, excluding digital-only tracks. Zero for media which are not played on a
+    /// turntable.
+    pub duration: TrackDuration,
+    /// The equipment which was active when a record was played. Unset for other media.
+    pub turntable_id: Option<String>,
+    pub cartridge_id: Option<String>,
+    pub stylus_id: Option<String>,
+    pub notes: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl From<PlaysJoin> for Play {
+    fn from(pj: PlaysJoin) -> Self {
+        Self {
+            id: pj.play_id,
+            media_id: pj.media_id,
+            title: pj.media_title,
+            played_at: pj.play_played_at,
+            sides: pj.play_sides,
+            duration: TrackDuration(pj.play_duration),
+            turntable_id: pj.play_turntable_id,
+            cartridge_id: pj.play_cartridge_id,
+            stylus_id: pj.play_stylus_id,
+            notes: pj.play_notes,
+            created_at: pj.play_created_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct GetPlaysQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Logs a play of a media entry. If `played_at` is omitted, the current time is used. If no `sides` are given, the
+/// whole media entry was played. Plays of records are attributed to the currently active equipment.
+#[derive(Deserialize)]
+pub struct CreatePlayRequest {
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub played_at: Option<OffsetDateTime>,
+    #[serde(default)]
+    pub sides: Vec<String>,
+    #[serde(default)]
+    pub notes: String,
+}
+
+#[derive(Serialize)]
+pub struct GetPlaysResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    plays: Option<Vec<Play>>,
+}
+
+impl GetPlaysResponse {
+    pub fn success(plays: Vec<Play>, total: i64) -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+            total: Some(total),
+            plays: Some(plays),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            total: None,
+            plays: None,
+        }
+    }
+}
+
+impl IntoResponse for GetPlaysResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
+
+/// Response of logging a play. The `warning` is set if the stylus the play was attributed to is wearing out.
+#[derive(Serialize)]
+pub struct CreatePlayResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    warning: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    play: Option<Play>,
+}
+
+impl CreatePlayResponse {
+    pub fn success(play: Play, warning: Option<String>) -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+            warning,
+            play: Some(play),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            warning: None,
+            play: None,
+        }
+    }
+}
+
+impl IntoResponse for CreatePlayResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
+
+#[derive(Serialize)]
+pub struct DeletePlayResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl DeletePlayResponse {
+    pub fn success() -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+        }
+    }
+}
+
+impl IntoResponse for DeletePlayResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
@@ -0,0 +1,239 @@
+use axum::{response::IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::types::Media;
+
+/// Pressing and variant details which distinguish copies of the same release.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Pressing {
+    pub plant: Option<String>,
+    pub country: Option<String>,
+    /// Colour or variant description, e.g. "Clear with red splatter".
+    pub variant: Option<String>,
+    /// Number of this copy of a limited edition, e.g. 123 of 500.
+    pub edition_number: Option<i32>,
+    pub edition_size: Option<i32>,
+    #[serde(default)]
+    pub matrices: Vec<Matrix>,
+}
+
+/// The matrix/runout etching of one side.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Matrix {
+    pub side: String,
+    pub runout: String,
+}
+
+impl Pressing {
+    /// Validates the pressing details and returns them trimmed. Empty texts are dropped, sides are uppercased and
+    /// every side can only have one matrix.
+    pub fn normalize(self) -> Result<Self, String> {
+        let trim =
+            |text: Option<String>| text.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+
+        for value in [self.edition_number, self.edition_size]
+            .into_iter()
+            .flatten()
+        {
+            if value < 1 {
+                return Err(format!(
+                    "Invalid edition numbering {}, numbers start at 1",
+                    value
+                ));
+            }
+        }
+
+        if let (Some(number), Some(size)) = (self.edition_number, self.edition_size) {
+            if number > size {
+                return Err(format!(
+                    "Invalid edition number {}, the edition is limited to {}",
+                    number, size
+                ));
+            }
+        }
+
+        let mut matrices: Vec<Matrix> = Vec::new();
+        for matrix in self.matrices {
+            let side = matrix.side.trim().to_uppercase();
+            let runout = matrix.runout.trim().to_string();
+
+            if side.is_empty() || side.len() > 8 {
+                return Err(format!("Invalid matrix side '{}'", side));
+            }
+
+            if runout.is_empty() {
+                return Err(format!("Missing runout for side '{}'", side));
+            }
+
+            if matrices.iter().any(|m| m.side == side) {
+                return Err(format!("Duplicate matrix for side '{}'", side));
+            }
+
+            matrices.push(Matrix { side, runout });
+        }
+
+        Ok(Self {
+            plant: trim(self.plant),
+            country: trim(self.country),
+            variant: trim(self.variant),
+            edition_number: self.edition_number,
+            edition_size: self.edition_size,
+            matrices,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RunoutSearchQuery {
+    pub q: String,
+    pub limit: Option<i64>,
+}
+
+/// A media entry with at least one matching runout etching.
+#[derive(Serialize)]
+pub struct RunoutSearchResult {
+    pub matches: Vec<Matrix>,
+    pub entry: Media,
+}
+
+#[derive(Serialize)]
+pub struct RunoutSearchResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    results: Option<Vec<RunoutSearchResult>>,
+}
+
+impl RunoutSearchResponse {
+    pub fn success(results: Vec<RunoutSearchResult>) -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+            results: Some(results),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            results: None,
+        }
+    }
+}
+
+impl IntoResponse for RunoutSearchResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edition(number: Option<i32>, size: Option<i32>) -> Pressing {
+        Pressing {
+            edition_number: number,
+            edition_size: size,
+            ..Default::default()
+        }
+    }
+
+    fn matrix(side: &str, runout: &str) -> Matrix {
+        Matrix {
+            side: side.to_string(),
+            runout: runout.to_string(),
+        }
+    }
+
+    fn with_matrices(matrices: Vec<Matrix>) -> Pressing {
+        Pressing {
+            matrices,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn accepts_edition_numbering() {
+        for (number, size) in [
+            (None, None),
+            (Some(1), None),
+            (None, Some(500)),
+            (Some(123), Some(500)),
+            (Some(500), Some(500)),
+        ] {
+            let pressing = edition(number, size).normalize().unwrap();
+            assert_eq!(
+                (pressing.edition_number, pressing.edition_size),
+                (number, size)
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_edition_numbering() {
+        for (number, size) in [
+            (Some(0), None),
+            (None, Some(0)),
+            (Some(-1), Some(500)),
+            (Some(501), Some(500)),
+        ] {
+            assert!(
+                edition(number, size).normalize().is_err(),
+                "{:?} of {:?} was accepted",
+                number,
+                size
+            );
+        }
+    }
+
+    #[test]
+    fn normalizes_matrices() {
+        let pressing = with_matrices(vec![matrix(" a ", " YEX 773-1 "), matrix("b", "YEX 774-1")])
+            .normalize()
+            .unwrap();
+        let matrices = pressing
+            .matrices
+            .iter()
+            .map(|m| (m.side.as_str(), m.runout.as_str()))
+            .collect::<Vec<(&str, &str)>>();
+        assert_eq!(matrices, vec![("A", "YEX 773-1"), ("B", "YEX 774-1")]);
+    }
+
+    #[test]
+    fn rejects_duplicate_sides() {
+        assert!(with_matrices(vec![matrix("A", "1"), matrix("a ", "2")])
+            .normalize()
+            .is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_matrices() {
+        assert!(with_matrices(vec![matrix(" ", "1")]).normalize().is_err());
+        assert!(with_matrices(vec![matrix("SIDEAAAAA", "1")])
+            .normalize()
+            .is_err());
+        assert!(with_matrices(vec![matrix("A", "  ")]).normalize().is_err());
+    }
+
+    #[test]
+    fn drops_empty_texts() {
+        let pressing = Pressing {
+            plant: Some(String::from("  ")),
+            country: Some(String::from(" UK ")),
+            variant: Some(String::new()),
+            ..Default::default()
+        }
+        .normalize()
+        .unwrap();
+        assert_eq!(pressing.plant, None);
+        assert_eq!(pressing.country, Some(String::from("UK")));
+        assert_eq!(pressing.variant, None);
+    }
+}
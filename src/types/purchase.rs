@@ -0,0 +1,292 @@
+use std::{fmt, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::types::{Shop, ShopRef};
+
+/// The most decimal places of any ISO 4217 currency, e.g. of CLF.
+const MAX_EXPONENT: u32 = 4;
+
+/// Returns the ISO 4217 exponent of the `currency`, i.e. the number of decimal places of its minor unit. Currencies not
+/// listed have two decimal places, like EUR and USD.
+fn currency_exponent(currency: &str) -> u32 {
+    match currency {
+        "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF" | "UGX"
+        | "UYI" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+        "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+        "CLF" | "UYW" => 4,
+        _ => 2,
+    }
+}
+
+/// An amount of money in the minor unit of its currency, e.g. cents or yen. Accepted and emitted as a decimal string
+/// with as many fractional digits as the currency has decimal places, e.g. `24.99` EUR, `1500` JPY or `1.250` KWD.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct Amount {
+    pub minor: i64,
+    /// The number of decimal places of the minor unit.
+    pub exponent: u32,
+}
+
+impl Amount {
+    /// Returns an amount of `minor` units of the `currency`.
+    pub fn new(minor: i64, currency: &str) -> Self {
+        Self {
+            minor,
+            exponent: currency_exponent(currency),
+        }
+    }
+
+    /// Returns the amount in a minor unit with `exponent` decimal places, or `None` if the amount has more decimal
+    /// places or does not fit.
+    fn rescale(self, exponent: u32) -> Option<Self> {
+        let minor = if exponent >= self.exponent {
+            self.minor
+                .checked_mul(10i64.checked_pow(exponent - self.exponent)?)?
+        } else {
+            let factor = 10i64.pow(self.exponent - exponent);
+            if self.minor % factor != 0 {
+                return None;
+            }
+            self.minor / factor
+        };
+
+        Some(Self { minor, exponent })
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.minor < 0 { "-" } else { "" };
+        let minor = self.minor.unsigned_abs();
+        if self.exponent == 0 {
+            return write!(f, "{}{}", sign, minor);
+        }
+
+        let factor = 10u64.pow(self.exponent);
+        write!(
+            f,
+            "{}{}.{:0width$}",
+            sign,
+            minor / factor,
+            minor % factor,
+            width = self.exponent as usize
+        )
+    }
+}
+
+impl FromStr for Amount {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid amount '{}', expected e.g. 24.99", s);
+
+        let (units, fraction) = s.trim().split_once('.').unwrap_or((s.trim(), ""));
+        if units.is_empty()
+            || fraction.len() > MAX_EXPONENT as usize
+            || !units.chars().all(|c| c.is_ascii_digit())
+            || !fraction.chars().all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        let exponent = fraction.len() as u32;
+        let units: i64 = units.parse().map_err(|_| invalid())?;
+        let fraction: i64 = if fraction.is_empty() {
+            0
+        } else {
+            fraction.parse().map_err(|_| invalid())?
+        };
+
+        match units
+            .checked_mul(10i64.pow(exponent))
+            .and_then(|u| u.checked_add(fraction))
+        {
+            Some(minor) => Ok(Self { minor, exponent }),
+            None => Err(invalid()),
+        }
+    }
+}
+
+impl Serialize for Amount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Amount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Price {
+    pub amount: Amount,
+    /// ISO 4217 currency code, e.g. `EUR`.
+    pub currency: String,
+}
+
+impl Price {
+    /// Validates the currency code of the price and returns the price with an uppercased currency code and the amount
+    /// in the minor unit of the currency. Amounts with more decimal places than the currency has are rejected.
+    pub fn normalize(self) -> Result<Self, String> {
+        let currency = self.currency.trim().to_uppercase();
+        if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(format!(
+                "Invalid currency '{}', expected an ISO 4217 code like EUR",
+                self.currency
+            ));
+        }
+
+        let exponent = currency_exponent(&currency);
+        let amount = match self.amount.rescale(exponent) {
+            Some(amount) => amount,
+            None if self.amount.exponent > exponent => {
+                return Err(format!(
+                    "Invalid amount '{}', {} has {} decimal places",
+                    self.amount, currency, exponent
+                ))
+            }
+            None => return Err(format!("Invalid amount '{}', too large", self.amount)),
+        };
+
+        Ok(Self { amount, currency })
+    }
+}
+
+/// Details of how and where a media entry was bought, in addition to its purchase date and buy condition.
+#[derive(Serialize, Clone, Default)]
+pub struct Purchase {
+    pub price: Option<Price>,
+    pub shop: Option<Shop>,
+    /// The individual seller, e.g. a marketplace user or a person at a record fair.
+    pub seller: Option<String>,
+    pub order_reference: Option<String>,
+}
+
+/// Purchase details in a media payload. The shop is referenced like labels and artists, either by ID or as a new
+/// shop.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct PurchaseRequest {
+    pub price: Option<Price>,
+    pub shop: Option<ShopRef>,
+    pub seller: Option<String>,
+    pub order_reference: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn amount(minor: i64, exponent: u32) -> Amount {
+        Amount { minor, exponent }
+    }
+
+    fn price(amount: &str, currency: &str) -> Result<Price, String> {
+        Price {
+            amount: amount.parse()?,
+            currency: currency.to_string(),
+        }
+        .normalize()
+    }
+
+    #[test]
+    fn parses_amounts() {
+        assert_eq!("24.99".parse(), Ok(amount(2499, 2)));
+        assert_eq!("5".parse(), Ok(amount(5, 0)));
+        assert_eq!("5.".parse(), Ok(amount(5, 0)));
+        assert_eq!("0.5".parse(), Ok(amount(5, 1)));
+        assert_eq!("0.05".parse(), Ok(amount(5, 2)));
+        assert_eq!("1.250".parse(), Ok(amount(1250, 3)));
+        assert_eq!("0.0001".parse(), Ok(amount(1, 4)));
+        assert_eq!("007.10".parse(), Ok(amount(710, 2)));
+        assert_eq!(" 12.00 ".parse(), Ok(amount(1200, 2)));
+        assert_eq!("92233720368547758.07".parse(), Ok(amount(i64::MAX, 2)));
+    }
+
+    #[test]
+    fn rejects_invalid_amounts() {
+        for input in [
+            "", ".", ".5", "1.23456", "-1", "+1", "1,50", "1.5.0", "abc", "1e3", "1 000", "1.-5",
+        ] {
+            assert!(input.parse::<Amount>().is_err(), "'{}' was accepted", input);
+        }
+    }
+
+    #[test]
+    fn rejects_overflowing_amounts() {
+        assert!("92233720368547758.08".parse::<Amount>().is_err());
+        assert!("9223372036854775808".parse::<Amount>().is_err());
+        assert!("99999999999999999999".parse::<Amount>().is_err());
+    }
+
+    #[test]
+    fn displays_amounts_in_the_minor_unit_of_the_currency() {
+        assert_eq!(Amount::new(2499, "EUR").to_string(), "24.99");
+        assert_eq!(Amount::new(5, "USD").to_string(), "0.05");
+        assert_eq!(Amount::new(0, "EUR").to_string(), "0.00");
+        assert_eq!(Amount::new(-1250, "EUR").to_string(), "-12.50");
+        assert_eq!(Amount::new(1500, "JPY").to_string(), "1500");
+        assert_eq!(Amount::new(1250, "KWD").to_string(), "1.250");
+        assert_eq!(Amount::new(5, "CLF").to_string(), "0.0005");
+        assert_eq!(
+            Amount::new(i64::MIN, "EUR").to_string(),
+            "-92233720368547758.08"
+        );
+    }
+
+    #[test]
+    fn round_trips_amounts() {
+        for currency in ["EUR", "JPY", "KWD", "CLF"] {
+            for minor in [0, 1, 50, 999, 2499, 100_000, i64::MAX] {
+                let amount = Amount::new(minor, currency);
+                assert_eq!(amount.to_string().parse(), Ok(amount));
+            }
+        }
+    }
+
+    #[test]
+    fn normalizes_currencies() {
+        assert_eq!(price("24.99", "eur").unwrap().currency, "EUR");
+        assert_eq!(price("24.99", " usd ").unwrap().currency, "USD");
+        for currency in ["", "EU", "EURO", "E1R", "€"] {
+            assert!(
+                price("24.99", currency).is_err(),
+                "'{}' was accepted",
+                currency
+            );
+        }
+    }
+
+    #[test]
+    fn stores_amounts_in_the_minor_unit_of_the_currency() {
+        assert_eq!(price("24.99", "EUR").unwrap().amount, amount(2499, 2));
+        assert_eq!(price("5", "EUR").unwrap().amount, amount(500, 2));
+        assert_eq!(price("0.5", "EUR").unwrap().amount, amount(50, 2));
+        assert_eq!(price("1500", "JPY").unwrap().amount, amount(1500, 0));
+        assert_eq!(price("1500.00", "jpy").unwrap().amount, amount(1500, 0));
+        assert_eq!(price("1.25", "KWD").unwrap().amount, amount(1250, 3));
+        assert_eq!(price("1.250", "KWD").unwrap().amount, amount(1250, 3));
+        assert_eq!(price("0.5", "CLF").unwrap().amount, amount(5000, 4));
+    }
+
+    #[test]
+    fn rejects_more_decimal_places_than_the_currency_has() {
+        assert!(price("1.234", "EUR").is_err());
+        assert!(price("1500.5", "JPY").is_err());
+        assert!(price("1.2345", "KWD").is_err());
+    }
+
+    #[test]
+    fn rejects_amounts_too_large_for_the_currency() {
+        assert!(price("92233720368547758.07", "KWD").is_err());
+        assert!(price("9223372036854775807", "EUR").is_err());
+        assert_eq!(
+            price("9223372036854775807", "JPY").unwrap().amount,
+            amount(i64::MAX, 0)
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use axum::{response::IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::types::time::{Date, OffsetDateTime};
+
+use crate::types::{
+    Artist, BuyCondition, Condition, ConditionGrading, Grade, GradingScale, Link, Media,
+    MediaFormat, MediaLabel, MediaType, Pressing, Purchase, PurchaseRequest, ReleaseDate,
+    SideRuntime, Track, TrackDuration,
+};
+
+/// A published record. Everything printed on the record, like title, format, artists, labels and tracks, belongs to
+/// the release, while each owned copy of it is a media entry.
+#[derive(Serialize)]
+pub struct Release {
+    pub id: String,
+    pub title: String,
+    pub media_type: MediaType,
+    pub format: MediaFormat,
+    pub artists: Vec<Artist>,
+    pub labels: Vec<MediaLabel>,
+    pub tracks: Vec<Track>,
+    /// Total runtime of all tracks.
+    pub runtime: TrackDuration,
+    pub side_runtimes: Vec<SideRuntime>,
+    pub release_date: ReleaseDate,
+    pub links: Vec<Link>,
+    /// The owned copies of the release, oldest first.
+    pub copies: Vec<ReleaseCopy>,
+}
+
+/// An owned copy of a release, i.e. the fields of a media entry which differ between copies.
+#[derive(Serialize)]
+pub struct ReleaseCopy {
+    pub id: String,
+    pub pressing: Pressing,
+    pub purchase_date: Date,
+    pub purchase: Purchase,
+    pub media_condition: Grade,
+    pub sleeve_condition: Grade,
+    pub latest_grading: Option<ConditionGrading>,
+    pub bought: BuyCondition,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub modified_at: OffsetDateTime,
+    pub notes: String,
+}
+
+impl Release {
+    /// Builds a release from the media entries of its copies. All `entries` have to belong to the same release.
+    /// Returns `None` if there are no entries.
+    pub fn from_copies(entries: Vec<Media>) -> Option<Self> {
+        let mut entries = entries.into_iter();
+        let first = entries.next()?;
+
+        let mut release = Self {
+            id: first.release_id.clone(),
+            title: first.title.clone(),
+            media_type: first.media_type.clone(),
+            format: first.format.clone(),
+            artists: first.artists.clone(),
+            labels: first.labels.clone(),
+            tracks: first.tracks.clone(),
+            runtime: first.runtime,
+            side_runtimes: first.side_runtimes.clone(),
+            release_date: first.release_date,
+            links: first.links.clone(),
+            copies: Vec::new(),
+        };
+
+        release.copies = std::iter::once(first)
+            .chain(entries)
+            .map(ReleaseCopy::from)
+            .collect();
+        Some(release)
+    }
+
+    /// Presents the conditions of all copies in the grading `scale`.
+    pub fn set_scale(&mut self, scale: GradingScale) {
+        for copy in &mut self.copies {
+            copy.media_condition.scale = scale;
+            copy.sleeve_condition.scale = scale;
+            if let Some(grading) = &mut copy.latest_grading {
+                grading.set_scale(scale);
+            }
+        }
+    }
+}
+
+impl From<Media> for ReleaseCopy {
+    fn from(media: Media) -> Self {
+        Self {
+            id: media.id,
+            pressing: media.pressing,
+            purchase_date: media.purchase_date,
+            purchase: media.purchase,
+            media_condition: media.media_condition,
+            sleeve_condition: media.sleeve_condition,
+            latest_grading: media.latest_grading,
+            bought: media.bought,
+            created_at: media.created_at,
+            modified_at: media.modified_at,
+            notes: media.notes,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct GetReleasesQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    /// Grading scale the conditions of the copies are returned in.
+    #[serde(default)]
+    pub scale: GradingScale,
+}
+
+/// Adds another copy of an existing release. The created copy is returned as a media entry.
+#[derive(Deserialize)]
+pub struct CreateCopyRequest {
+    #[serde(default)]
+    pub pressing: Pressing,
+    pub purchase_date: Date,
+    #[serde(default)]
+    pub purchase: PurchaseRequest,
+    pub media_condition: Condition,
+    pub sleeve_condition: Condition,
+    pub bought: BuyCondition,
+    #[serde(default)]
+    pub notes: String,
+}
+
+#[derive(Serialize)]
+pub struct GetReleasesResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    releases: Option<Vec<Release>>,
+}
+
+impl GetReleasesResponse {
+    pub fn success(releases: Vec<Release>, total: i64) -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+            total: Some(total),
+            releases: Some(releases),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            total: None,
+            releases: None,
+        }
+    }
+}
+
+impl IntoResponse for GetReleasesResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
+
+#[derive(Serialize)]
+pub struct GetReleaseResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    release: Option<Release>,
+}
+
+impl GetReleaseResponse {
+    pub fn success(release: Release) -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+            release: Some(release),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            release: None,
+        }
+    }
+}
+
+impl IntoResponse for GetReleaseResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
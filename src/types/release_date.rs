@@ -0,0 +1,159 @@
+use std::{fmt, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use sqlx::Type;
+use time::{Date, Month};
+
+/// How precisely a release date is known.
+#[derive(Type, Debug, Clone, Copy, PartialEq, Eq)]
+#[sqlx(type_name = "varchar")]
+pub enum DatePrecision {
+    Year,
+    Month,
+    Day,
+}
+
+/// A release date which may only be known to the year or month. Accepted and emitted as `yyyy`, `yyyy-mm` or
+/// `yyyy-mm-dd`. `date` is the first day of the period, e.g. 1959-01-01 for `1959`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReleaseDate {
+    pub date: Date,
+    pub precision: DatePrecision,
+}
+
+impl fmt::Display for ReleaseDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = (self.date.year(), self.date.month() as u8, self.date.day());
+        match self.precision {
+            DatePrecision::Year => write!(f, "{:04}", year),
+            DatePrecision::Month => write!(f, "{:04}-{:02}", year, month),
+            DatePrecision::Day => write!(f, "{:04}-{:02}-{:02}", year, month, day),
+        }
+    }
+}
+
+impl FromStr for ReleaseDate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid date '{}', expected yyyy, yyyy-mm or yyyy-mm-dd", s);
+
+        let parts = s.trim().split('-').collect::<Vec<&str>>();
+        let lengths = parts.iter().map(|p| p.len()).collect::<Vec<usize>>();
+        if !matches!(lengths[..], [4] | [4, 2] | [4, 2, 2])
+            || !parts.iter().all(|p| p.chars().all(|c| c.is_ascii_digit()))
+        {
+            return Err(invalid());
+        }
+
+        let year: i32 = parts[0].parse().map_err(|_| invalid())?;
+        let month: u8 = parts
+            .get(1)
+            .map_or(Ok(1), |m| m.parse())
+            .map_err(|_| invalid())?;
+        let day: u8 = parts
+            .get(2)
+            .map_or(Ok(1), |d| d.parse())
+            .map_err(|_| invalid())?;
+
+        let month = Month::try_from(month).map_err(|_| invalid())?;
+        let date = Date::from_calendar_date(year, month, day).map_err(|_| invalid())?;
+        let precision = match parts.len() {
+            1 => DatePrecision::Year,
+            2 => DatePrecision::Month,
+            _ => DatePrecision::Day,
+        };
+
+        Ok(Self { date, precision })
+    }
+}
+
+impl Serialize for ReleaseDate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ReleaseDate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: Month, day: u8) -> Date {
+        Date::from_calendar_date(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn parses_dates_of_every_precision() {
+        assert_eq!(
+            "1959".parse::<ReleaseDate>(),
+            Ok(ReleaseDate {
+                date: date(1959, Month::January, 1),
+                precision: DatePrecision::Year,
+            })
+        );
+        assert_eq!(
+            "1959-08".parse::<ReleaseDate>(),
+            Ok(ReleaseDate {
+                date: date(1959, Month::August, 1),
+                precision: DatePrecision::Month,
+            })
+        );
+        assert_eq!(
+            " 1959-08-17 ".parse::<ReleaseDate>(),
+            Ok(ReleaseDate {
+                date: date(1959, Month::August, 17),
+                precision: DatePrecision::Day,
+            })
+        );
+    }
+
+    #[test]
+    fn accepts_leap_days() {
+        assert!("2024-02-29".parse::<ReleaseDate>().is_ok());
+        assert!("2000-02-29".parse::<ReleaseDate>().is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_dates() {
+        for input in [
+            "",
+            "59",
+            "19590",
+            "1959-8",
+            "1959-08-7",
+            "1959-00",
+            "1959-13",
+            "1959-08-00",
+            "1959-08-32",
+            "2024-02-30",
+            "2023-02-29",
+            "1900-02-29",
+            "1959/08/17",
+            "1959-08-17-01",
+            "+959",
+            "-959",
+            "1959-08-",
+            "abcd",
+        ] {
+            assert!(
+                input.parse::<ReleaseDate>().is_err(),
+                "'{}' was accepted",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn round_trips_dates() {
+        for input in ["1959", "1959-08", "1959-08-17", "0999-01-01"] {
+            assert_eq!(input.parse::<ReleaseDate>().unwrap().to_string(), input);
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use axum::{response::IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::types::Media;
+
+#[derive(Deserialize)]
+pub struct SearchQuery {
+    pub q: String,
+    pub limit: Option<i64>,
+}
+
+/// A single matching field of a search result. The `text` is HTML-escaped and the matched terms in it are wrapped in
+/// `<b>` tags, so it can be rendered as HTML as is.
+#[derive(Serialize, Clone)]
+pub struct SearchHighlight {
+    pub field: String,
+    pub text: String,
+}
+
+#[derive(Serialize)]
+pub struct SearchResult {
+    pub rank: f32,
+    pub highlights: Vec<SearchHighlight>,
+    pub entry: Media,
+}
+
+#[derive(Serialize)]
+pub struct SearchResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    results: Option<Vec<SearchResult>>,
+}
+
+impl SearchResponse {
+    pub fn success(results: Vec<SearchResult>) -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+            results: Some(results),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            results: None,
+        }
+    }
+}
+
+impl IntoResponse for SearchResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
@@ -0,0 +1,186 @@
+use axum::{response::IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::types::{Amount, Link, LinkRequest};
+
+/// A record store, online shop or marketplace media entries were bought at.
+#[derive(Serialize, Clone)]
+pub struct Shop {
+    pub id: String,
+    pub name: String,
+    pub city: Option<String>,
+    pub online: bool,
+    pub links: Vec<Link>,
+}
+
+/// A shop together with the money spent there. Spending is aggregated per currency, as prices are not converted.
+#[derive(Serialize)]
+pub struct ShopSummary {
+    #[serde(flatten)]
+    pub shop: Shop,
+    /// Number of media entries bought at the shop.
+    pub purchases: i64,
+    pub spending: Vec<Spending>,
+}
+
+#[derive(Serialize)]
+pub struct Spending {
+    pub currency: String,
+    pub total: Amount,
+    /// Number of purchases with a price in this currency.
+    pub purchases: i64,
+}
+
+/// A shop referenced in a media payload. Either points to an already existing shop by its ID or describes a new shop
+/// which gets created alongside the media entry.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum ShopRef {
+    Existing {
+        id: String,
+    },
+    New {
+        name: String,
+        city: Option<String>,
+        #[serde(default)]
+        online: bool,
+        #[serde(default)]
+        links: Vec<LinkRequest>,
+    },
+}
+
+#[derive(Deserialize)]
+pub struct GetShopsQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateShopRequest {
+    pub name: String,
+    pub city: Option<String>,
+    #[serde(default)]
+    pub online: bool,
+    #[serde(default)]
+    pub links: Vec<LinkRequest>,
+}
+
+/// A sparse update of a shop. Omitted fields keep their current value, an empty city removes the city.
+#[derive(Deserialize)]
+pub struct UpdateShopRequest {
+    pub name: Option<String>,
+    pub city: Option<String>,
+    pub online: Option<bool>,
+    /// Replaces all links of the shop.
+    pub links: Option<Vec<LinkRequest>>,
+}
+
+#[derive(Serialize)]
+pub struct GetShopsResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shops: Option<Vec<ShopSummary>>,
+}
+
+impl GetShopsResponse {
+    pub fn success(shops: Vec<ShopSummary>, total: i64) -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+            total: Some(total),
+            shops: Some(shops),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            total: None,
+            shops: None,
+        }
+    }
+}
+
+impl IntoResponse for GetShopsResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
+
+/// Response of the endpoints which return a single shop, i.e. get, create and update.
+#[derive(Serialize)]
+pub struct ShopResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shop: Option<ShopSummary>,
+}
+
+impl ShopResponse {
+    pub fn success(shop: ShopSummary) -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+            shop: Some(shop),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            shop: None,
+        }
+    }
+}
+
+impl IntoResponse for ShopResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
+
+#[derive(Serialize)]
+pub struct DeleteShopResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl DeleteShopResponse {
+    pub fn success() -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+        }
+    }
+}
+
+impl IntoResponse for DeleteShopResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
@@ -0,0 +1,119 @@
+use axum::{response::IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::types::{Amount, Grade, GradingScale, MediaType};
+
+#[derive(Deserialize)]
+pub struct StatsQuery {
+    /// Number of labels and artists listed, the ones with the most media entries first.
+    pub top: Option<i64>,
+    /// Grading scale the conditions are returned in.
+    #[serde(default)]
+    pub scale: GradingScale,
+}
+
+/// Aggregated numbers about the whole collection. Every list only contains groups with at least one media entry.
+#[derive(Serialize)]
+pub struct Stats {
+    /// Total number of media entries.
+    pub total: i64,
+    pub media_types: Vec<MediaTypeCount>,
+    pub media_conditions: Vec<ConditionCount>,
+    pub sleeve_conditions: Vec<ConditionCount>,
+    pub decades: Vec<DecadeCount>,
+    pub labels: Vec<NamedCount>,
+    pub artists: Vec<NamedCount>,
+    /// Money spent per purchase year. Spending is aggregated per currency, as prices are not converted.
+    pub spending: Vec<YearlySpending>,
+    /// Number of media entries added to the collection per month, based on their creation time in UTC.
+    pub added: Vec<MonthCount>,
+}
+
+impl Stats {
+    pub fn set_scale(&mut self, scale: GradingScale) {
+        self.media_conditions
+            .iter_mut()
+            .chain(self.sleeve_conditions.iter_mut())
+            .for_each(|c| c.condition.scale = scale);
+    }
+}
+
+#[derive(Serialize)]
+pub struct MediaTypeCount {
+    pub media_type: MediaType,
+    pub count: i64,
+}
+
+#[derive(Serialize)]
+pub struct ConditionCount {
+    pub condition: Grade,
+    pub count: i64,
+}
+
+#[derive(Serialize)]
+pub struct DecadeCount {
+    /// First year of the decade, e.g. 1970 for the seventies.
+    pub decade: i32,
+    pub count: i64,
+}
+
+/// Number of media entries of a label or an artist.
+#[derive(Serialize)]
+pub struct NamedCount {
+    pub id: String,
+    pub name: String,
+    pub count: i64,
+}
+
+#[derive(Serialize)]
+pub struct YearlySpending {
+    pub year: i32,
+    pub currency: String,
+    pub total: Amount,
+    /// Number of purchases with a price in this currency.
+    pub purchases: i64,
+}
+
+#[derive(Serialize)]
+pub struct MonthCount {
+    /// Month formatted as `yyyy-mm`.
+    pub month: String,
+    pub count: i64,
+}
+
+#[derive(Serialize)]
+pub struct StatsResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stats: Option<Stats>,
+}
+
+impl StatsResponse {
+    pub fn success(stats: Stats) -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+            stats: Some(stats),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            stats: None,
+        }
+    }
+}
+
+impl IntoResponse for StatsResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
@@ -1,11 +1,489 @@
-use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
 
-#[derive(Serialize, Deserialize, Clone)]
+use axum::{response::IntoResponse, Json};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::json;
+
+use crate::{
+    store::models::TracksJoin,
+    types::{Link, LinkRequest, MediaType},
+};
+
+#[derive(Serialize, Clone)]
 pub struct Track {
     pub id: String,
     pub title: String,
-    pub duration: i32,
-    pub record_side: String,
+    pub duration: TrackDuration,
+    pub position: TrackPosition,
     pub digital: bool,
-    pub urls: String,
+    pub links: Vec<Link>,
+}
+
+impl From<TracksJoin> for Track {
+    fn from(tj: TracksJoin) -> Self {
+        Self {
+            id: tj.track_id,
+            title: tj.track_title,
+            duration: TrackDuration(tj.track_duration),
+            position: TrackPosition {
+                disc: tj.track_disc,
+                side: tj.track_record_side,
+                index: tj.track_position,
+            },
+            digital: tj.track_digital,
+            links: Vec::new(),
+        }
+    }
+}
+
+/// Position of a track on its media entry. Vinyl sides are lettered across discs, e.g. side C is the first side of
+/// the second disc. Tapes and reels only have the sides A and B, 8-tracks have the programs 1 to 4 and CDs and
+/// MiniDiscs have no sides at all. Tracks of box sets may have a side letter.
+#[derive(Serialize, Clone, PartialEq, Eq)]
+pub struct TrackPosition {
+    pub disc: i32,
+    pub side: Option<String>,
+    /// 1-based index of the track on its side, or on its disc for media without sides.
+    pub index: i32,
+}
+
+impl TrackPosition {
+    /// Validates the `disc` and `side` of a track against the `media_type` and returns the normalized disc and side.
+    /// Side letters are uppercased, the disc of a vinyl track is derived from its side. If no disc is given, the
+    /// first disc is assumed.
+    pub fn normalize_side(
+        media_type: &MediaType,
+        disc: Option<i32>,
+        side: Option<&str>,
+    ) -> Result<(i32, Option<String>), String> {
+        if let Some(disc) = disc {
+            if disc < 1 {
+                return Err(format!("Invalid disc {}, discs start at 1", disc));
+            }
+        }
+
+        let side = side
+            .map(|s| s.trim().to_uppercase())
+            .filter(|s| !s.is_empty());
+
+        match (media_type, side) {
+            (MediaType::Cd | MediaType::MiniDisc, None) => Ok((disc.unwrap_or(1), None)),
+            (MediaType::Cd | MediaType::MiniDisc, Some(side)) => Err(format!(
+                "Invalid side '{}', CDs and MiniDiscs have no sides",
+                side
+            )),
+            // Box sets can hold records as well as CDs, so sides are optional and the disc is kept as is
+            (MediaType::BoxSet, None) => Ok((disc.unwrap_or(1), None)),
+            (MediaType::BoxSet, Some(side)) => match side.as_bytes() {
+                [letter] if letter.is_ascii_uppercase() => Ok((disc.unwrap_or(1), Some(side))),
+                _ => Err(format!("Invalid side '{}', expected a letter", side)),
+            },
+            (MediaType::EightTrack, None) => Err(String::from(
+                "Missing side, 8-track tracks are on one of the programs 1 to 4",
+            )),
+            (MediaType::EightTrack, Some(side)) => match side.as_str() {
+                "1" | "2" | "3" | "4" => Ok((disc.unwrap_or(1), Some(side))),
+                _ => Err(format!(
+                    "Invalid side '{}', 8-tracks only have the programs 1 to 4",
+                    side
+                )),
+            },
+            (_, None) => Err(String::from(
+                "Missing side, vinyl, tapes and reels have sides",
+            )),
+            (MediaType::Tape | MediaType::ReelToReel, Some(side)) => match side.as_str() {
+                "A" | "B" => Ok((disc.unwrap_or(1), Some(side))),
+                _ => Err(format!(
+                    "Invalid side '{}', tapes and reels only have sides A and B",
+                    side
+                )),
+            },
+            (MediaType::Vinyl, Some(side)) => {
+                let letter = match side.as_bytes() {
+                    [letter] if letter.is_ascii_uppercase() => *letter,
+                    _ => return Err(format!("Invalid side '{}', expected a letter", side)),
+                };
+
+                let side_disc = (letter - b'A') as i32 / 2 + 1;
+                match disc {
+                    Some(disc) if disc != side_disc => Err(format!(
+                        "Invalid disc {}, side {} is on disc {}",
+                        disc, side, side_disc
+                    )),
+                    _ => Ok((side_disc, Some(side))),
+                }
+            }
+        }
+    }
+}
+
+/// Duration of a track in seconds. Accepted and emitted as `mm:ss`, e.g. `09:22`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TrackDuration(pub i32);
+
+impl fmt::Display for TrackDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.0 / 60, self.0 % 60)
+    }
+}
+
+impl FromStr for TrackDuration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid duration '{}', expected mm:ss", s);
+
+        let (minutes, seconds) = match s.trim().split_once(':') {
+            Some(parts) => parts,
+            None => return Err(invalid()),
+        };
+
+        if minutes.is_empty()
+            || seconds.len() != 2
+            || !minutes.chars().all(|c| c.is_ascii_digit())
+            || !seconds.chars().all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        let minutes: i32 = minutes.parse().map_err(|_| invalid())?;
+        let seconds: i32 = seconds.parse().map_err(|_| invalid())?;
+        if seconds >= 60 {
+            return Err(invalid());
+        }
+
+        match minutes.checked_mul(60).and_then(|m| m.checked_add(seconds)) {
+            Some(total) => Ok(Self(total)),
+            None => Err(invalid()),
+        }
+    }
+}
+
+impl Serialize for TrackDuration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for TrackDuration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+/// The requested position of a track. The side is required for vinyl and tapes and must be omitted for CDs.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct TrackPositionRequest {
+    pub disc: Option<i32>,
+    pub side: Option<String>,
+    pub index: Option<i32>,
+}
+
+/// A new track. If no `index` is given, the track is appended to the end of its side. Otherwise it is inserted at
+/// that index and the following tracks on the side move back by one.
+#[derive(Deserialize)]
+pub struct CreateTrackRequest {
+    pub title: String,
+    pub duration: TrackDuration,
+    #[serde(default)]
+    pub position: TrackPositionRequest,
+    #[serde(default)]
+    pub digital: bool,
+    #[serde(default)]
+    pub links: Vec<LinkRequest>,
+}
+
+/// A sparse update of a track. Omitted fields keep their current value. Changing the disc, side or index moves the
+/// track, the remaining tracks on the affected sides are renumbered to close or open the gap.
+#[derive(Deserialize)]
+pub struct UpdateTrackRequest {
+    pub title: Option<String>,
+    pub duration: Option<TrackDuration>,
+    #[serde(default)]
+    pub position: TrackPositionRequest,
+    pub digital: Option<bool>,
+    /// Replaces all links of the track.
+    pub links: Option<Vec<LinkRequest>>,
+}
+
+/// A sparse update of a single track within a media update.
+#[derive(Deserialize)]
+pub struct TrackUpdate {
+    pub id: String,
+    #[serde(flatten)]
+    pub changes: UpdateTrackRequest,
+}
+
+/// Changes to the tracks of a media entry. The list is either replaced completely via `set` or edited one track at a
+/// time via `add`, `update` and `remove`.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct TracksPatch {
+    pub set: Option<Vec<CreateTrackRequest>>,
+    pub add: Vec<CreateTrackRequest>,
+    pub update: Vec<TrackUpdate>,
+    pub remove: Vec<String>,
+}
+
+impl TracksPatch {
+    pub fn is_empty(&self) -> bool {
+        self.set.is_none()
+            && self.add.is_empty()
+            && self.update.is_empty()
+            && self.remove.is_empty()
+    }
+}
+
+/// Response of the endpoints which return all tracks of a media entry, i.e. list and bulk replace.
+#[derive(Serialize)]
+pub struct GetTracksResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tracks: Option<Vec<Track>>,
+}
+
+impl GetTracksResponse {
+    pub fn success(tracks: Vec<Track>) -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+            tracks: Some(tracks),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            tracks: None,
+        }
+    }
+}
+
+impl IntoResponse for GetTracksResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
+
+/// Response of the endpoints which return a single track, i.e. add and update.
+#[derive(Serialize)]
+pub struct TrackResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    track: Option<Track>,
+}
+
+impl TrackResponse {
+    pub fn success(track: Track) -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+            track: Some(track),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            track: None,
+        }
+    }
+}
+
+impl IntoResponse for TrackResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
+
+#[derive(Serialize)]
+pub struct DeleteTrackResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl DeleteTrackResponse {
+    pub fn success() -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+        }
+    }
+}
+
+impl IntoResponse for DeleteTrackResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!("09:22".parse::<TrackDuration>(), Ok(TrackDuration(562)));
+        assert_eq!("0:00".parse::<TrackDuration>(), Ok(TrackDuration(0)));
+        assert_eq!(" 5:59 ".parse::<TrackDuration>(), Ok(TrackDuration(359)));
+        assert_eq!("123:45".parse::<TrackDuration>(), Ok(TrackDuration(7425)));
+    }
+
+    #[test]
+    fn rejects_invalid_durations() {
+        for input in [
+            "", "5", "5:60", "5:6", "5:060", ":30", "-1:30", "1:-3", "1:3a", "1.5:00", "1:00:00",
+        ] {
+            assert!(
+                input.parse::<TrackDuration>().is_err(),
+                "'{}' was accepted",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_overflowing_durations() {
+        assert!("35791394:59".parse::<TrackDuration>().is_err());
+        assert!("99999999999:00".parse::<TrackDuration>().is_err());
+    }
+
+    #[test]
+    fn displays_durations_as_minutes_and_seconds() {
+        assert_eq!(TrackDuration(562).to_string(), "09:22");
+        assert_eq!(TrackDuration(0).to_string(), "00:00");
+        assert_eq!(TrackDuration(7425).to_string(), "123:45");
+    }
+
+    #[test]
+    fn round_trips_durations() {
+        for seconds in [0, 59, 60, 562, 3599, 7425] {
+            let duration = TrackDuration(seconds);
+            assert_eq!(duration.to_string().parse::<TrackDuration>(), Ok(duration));
+        }
+    }
+
+    fn side(
+        media_type: MediaType,
+        disc: Option<i32>,
+        side: Option<&str>,
+    ) -> Result<(i32, Option<String>), String> {
+        TrackPosition::normalize_side(&media_type, disc, side)
+    }
+
+    #[test]
+    fn derives_the_disc_of_vinyl_sides() {
+        for (letter, disc) in [("A", 1), ("b", 1), ("C", 2), ("D", 2), ("E", 3), ("Z", 13)] {
+            assert_eq!(
+                side(MediaType::Vinyl, None, Some(letter)),
+                Ok((disc, Some(letter.to_uppercase())))
+            );
+        }
+        assert_eq!(
+            side(MediaType::Vinyl, Some(2), Some("C")),
+            Ok((2, Some(String::from("C"))))
+        );
+        assert_eq!(
+            side(MediaType::Vinyl, None, Some(" d ")),
+            Ok((2, Some(String::from("D"))))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_vinyl_sides() {
+        assert!(side(MediaType::Vinyl, Some(1), Some("C")).is_err());
+        assert!(side(MediaType::Vinyl, None, None).is_err());
+        assert!(side(MediaType::Vinyl, None, Some("")).is_err());
+        for input in ["AA", "1", "Ä", "-"] {
+            assert!(
+                side(MediaType::Vinyl, None, Some(input)).is_err(),
+                "'{}' was accepted",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_discs_below_one() {
+        assert!(side(MediaType::Vinyl, Some(0), Some("A")).is_err());
+        assert!(side(MediaType::Cd, Some(-1), None).is_err());
+    }
+
+    #[test]
+    fn accepts_only_sides_a_and_b_on_tapes_and_reels() {
+        for media_type in [MediaType::Tape, MediaType::ReelToReel] {
+            assert_eq!(
+                side(media_type.clone(), None, Some("a")),
+                Ok((1, Some(String::from("A"))))
+            );
+            assert_eq!(
+                side(media_type.clone(), Some(2), Some("B")),
+                Ok((2, Some(String::from("B"))))
+            );
+            assert!(side(media_type.clone(), None, Some("C")).is_err());
+            assert!(side(media_type, None, None).is_err());
+        }
+    }
+
+    #[test]
+    fn accepts_only_programs_one_to_four_on_8_tracks() {
+        for program in ["1", "2", "3", "4"] {
+            assert_eq!(
+                side(MediaType::EightTrack, None, Some(program)),
+                Ok((1, Some(String::from(program))))
+            );
+        }
+        for input in ["0", "5", "A", "12"] {
+            assert!(
+                side(MediaType::EightTrack, None, Some(input)).is_err(),
+                "'{}' was accepted",
+                input
+            );
+        }
+        assert!(side(MediaType::EightTrack, None, None).is_err());
+    }
+
+    #[test]
+    fn rejects_sides_on_cds_and_minidiscs() {
+        for media_type in [MediaType::Cd, MediaType::MiniDisc] {
+            assert_eq!(side(media_type.clone(), None, None), Ok((1, None)));
+            assert_eq!(side(media_type.clone(), Some(3), None), Ok((3, None)));
+            assert_eq!(side(media_type.clone(), Some(2), Some(" ")), Ok((2, None)));
+            assert!(side(media_type, None, Some("A")).is_err());
+        }
+    }
+
+    #[test]
+    fn keeps_the_disc_of_box_set_tracks() {
+        assert_eq!(side(MediaType::BoxSet, Some(3), None), Ok((3, None)));
+        assert_eq!(
+            side(MediaType::BoxSet, Some(1), Some("c")),
+            Ok((1, Some(String::from("C"))))
+        );
+        assert!(side(MediaType::BoxSet, None, Some("1")).is_err());
+    }
 }
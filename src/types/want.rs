@@ -0,0 +1,236 @@
+use axum::{response::IntoResponse, Json};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::json;
+use sqlx::{
+    types::time::{Date, OffsetDateTime},
+    Type,
+};
+
+use crate::types::{
+    Artist, ArtistRef, BuyCondition, Condition, CreateTrackRequest, Grade, GradingScale,
+    LinkRequest, MediaFormat, MediaLabel, MediaLabelRef, MediaType, Pressing, Price,
+    PurchaseRequest, ReleaseDate,
+};
+
+#[derive(Serialize, Deserialize, Type, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[sqlx(type_name = "varchar")]
+pub enum WantPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+/// A record which is wanted but not owned yet. Wants share the release fields of media entries, so they can be turned
+/// into a media entry once the record is bought.
+#[derive(Serialize)]
+pub struct Want {
+    pub id: String,
+    pub title: String,
+    pub media_type: MediaType,
+    pub artists: Vec<Artist>,
+    pub labels: Vec<MediaLabel>,
+    pub priority: WantPriority,
+    /// The most one is willing to pay for the record.
+    pub max_price: Option<Price>,
+    /// The worst media condition which is still acceptable.
+    pub min_condition: Option<Grade>,
+    pub notes: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub modified_at: OffsetDateTime,
+}
+
+impl Want {
+    pub fn set_scale(&mut self, scale: GradingScale) {
+        if let Some(condition) = &mut self.min_condition {
+            condition.scale = scale;
+        }
+    }
+}
+
+/// Deserializes a present value, including `null`, as `Some`. Combined with `#[serde(default)]` this tells omitted
+/// fields (`None`) apart from fields which are explicitly cleared (`Some(None)`).
+pub(crate) fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+#[derive(Deserialize)]
+pub struct GetWantsQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub priority: Option<WantPriority>,
+    /// Grading scale the minimum conditions are returned in.
+    #[serde(default)]
+    pub scale: GradingScale,
+}
+
+#[derive(Deserialize)]
+pub struct CreateWantRequest {
+    pub title: String,
+    pub media_type: MediaType,
+    pub artists: Vec<ArtistRef>,
+    #[serde(default)]
+    pub labels: Vec<MediaLabelRef>,
+    #[serde(default)]
+    pub priority: WantPriority,
+    pub max_price: Option<Price>,
+    pub min_condition: Option<Condition>,
+    #[serde(default)]
+    pub notes: String,
+}
+
+/// A sparse update of a want. Omitted fields keep their current value, the maximum price and minimum condition are
+/// removed by setting them to `null`.
+#[derive(Deserialize)]
+pub struct UpdateWantRequest {
+    pub title: Option<String>,
+    pub media_type: Option<MediaType>,
+    /// Replaces all artists of the want.
+    pub artists: Option<Vec<ArtistRef>>,
+    /// Replaces all labels of the want.
+    pub labels: Option<Vec<MediaLabelRef>>,
+    pub priority: Option<WantPriority>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub max_price: Option<Option<Price>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub min_condition: Option<Option<Condition>>,
+    pub notes: Option<String>,
+}
+
+/// Turns a want into an owned media entry. Title, media type, artists and labels are taken from the want, everything
+/// else describes the bought copy. The want is removed once the media entry is created.
+#[derive(Deserialize)]
+pub struct ConvertWantRequest {
+    pub release_date: ReleaseDate,
+    pub purchase_date: Date,
+    #[serde(default)]
+    pub purchase: PurchaseRequest,
+    pub media_condition: Condition,
+    pub sleeve_condition: Condition,
+    pub bought: BuyCondition,
+    #[serde(default)]
+    pub format: MediaFormat,
+    #[serde(default)]
+    pub pressing: Pressing,
+    #[serde(default)]
+    pub tracks: Vec<CreateTrackRequest>,
+    /// Notes of the media entry. Defaults to the notes of the want.
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub links: Vec<LinkRequest>,
+}
+
+#[derive(Serialize)]
+pub struct GetWantsResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    wants: Option<Vec<Want>>,
+}
+
+impl GetWantsResponse {
+    pub fn success(wants: Vec<Want>, total: i64) -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+            total: Some(total),
+            wants: Some(wants),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            total: None,
+            wants: None,
+        }
+    }
+}
+
+impl IntoResponse for GetWantsResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
+
+/// Response of the endpoints which return a single want, i.e. get, create and update.
+#[derive(Serialize)]
+pub struct WantResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    want: Option<Want>,
+}
+
+impl WantResponse {
+    pub fn success(want: Want) -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+            want: Some(want),
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+            want: None,
+        }
+    }
+}
+
+impl IntoResponse for WantResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}
+
+#[derive(Serialize)]
+pub struct DeleteWantResponse {
+    status: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl DeleteWantResponse {
+    pub fn success() -> Self {
+        Self {
+            status: String::from("success"),
+            error: None,
+        }
+    }
+
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            status: String::from("error"),
+            error: Some(message.into()),
+        }
+    }
+}
+
+impl IntoResponse for DeleteWantResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!(self));
+        body.into_response()
+    }
+}